@@ -0,0 +1,621 @@
+use crate::{
+    audio::{Sound, Volume},
+    schedule::Schedule,
+    weather::{Action, Adjustment, EVALUATE_AHEAD, Report, Rule, Units},
+};
+use chrono::{DateTime, Local, NaiveTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct Alarm {
+    pub id: u32,
+    pub label: String,
+    pub enabled: bool,
+    pub time: NaiveTime,
+    pub schedule: Schedule,
+    #[serde(default)]
+    pub sound: Sound,
+    #[serde(default)]
+    pub volume: Volume,
+    #[serde(default)]
+    pub rule: Option<Rule>,
+    /// What the rule decided for the upcoming ring, once it is close.
+    #[serde(default)]
+    pub adjustment: Option<Adjustment>,
+    /// Occurrence the user asked to skip.
+    pub skip: Option<DateTime<Local>>,
+    /// When the alarm fires next; `None` while disabled.
+    pub due: Option<DateTime<Local>>,
+    /// Pending snooze, fires regardless of the schedule.
+    pub snoozed_until: Option<DateTime<Local>>,
+    /// Most recent rings, oldest first.
+    pub history: Vec<Ring>,
+}
+
+/// How a ringing alarm was stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Dismissed,
+    Snoozed,
+    /// Nobody reacted before the ring timeout.
+    Missed,
+    /// Left silent by a weather rule.
+    Skipped,
+}
+
+impl Outcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Dismissed => "dismissed",
+            Outcome::Snoozed => "snoozed",
+            Outcome::Missed => "missed",
+            Outcome::Skipped => "skipped",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Ring {
+    pub at: DateTime<Local>,
+    pub outcome: Outcome,
+}
+
+const HISTORY_LEN: usize = 10;
+
+impl Alarm {
+    /// When the alarm rings next, counting snoozes and weather rules.
+    pub fn next_ring(&self) -> Option<DateTime<Local>> {
+        match (self.adjusted_due(), self.snoozed_until) {
+            (Some(due), Some(snooze)) => Some(due.min(snooze)),
+            (due, snooze) => due.or(snooze),
+        }
+    }
+
+    /// The scheduled ring as moved by the weather rule, `None` if the rule
+    /// skips it.
+    pub fn adjusted_due(&self) -> Option<DateTime<Local>> {
+        let due = self.due?;
+        match self.adjustment.as_ref().map(|adjustment| adjustment.action) {
+            Some(Action::Earlier(minutes)) => Some(due - TimeDelta::minutes(i64::from(minutes))),
+            Some(Action::Skip) => None,
+            None => Some(due),
+        }
+    }
+
+    fn skips_due(&self) -> bool {
+        self.adjustment
+            .as_ref()
+            .is_some_and(|adjustment| adjustment.action == Action::Skip)
+    }
+
+    pub fn last_ring(&self) -> Option<&Ring> {
+        self.history.last()
+    }
+
+    fn push_history(&mut self, at: DateTime<Local>, outcome: Outcome) {
+        self.history.push(Ring { at, outcome });
+        if self.history.len() > HISTORY_LEN {
+            self.history.remove(0);
+        }
+    }
+
+    fn rearm(&mut self, now: DateTime<Local>) {
+        self.adjustment = None;
+        if self.skip.is_some_and(|skip| skip <= now) {
+            self.skip = None;
+        }
+        self.due = if self.enabled {
+            let next = self.schedule.next_after(self.time, now);
+            match (next, self.skip) {
+                (Some(next), Some(skip)) if next == skip => {
+                    self.schedule.next_after(self.time, next)
+                }
+                _ => next,
+            }
+        } else {
+            None
+        };
+    }
+}
+
+/// An alarm on screen, waiting for someone to stop or snooze it.
+pub struct Ringing {
+    pub alarm_id: u32,
+    pub since: DateTime<Local>,
+}
+
+/// Parses an alarm time, `19:30` or 12-hour as in `7:30am` or `7 pm`.
+pub fn parse_time(input: &str) -> Result<NaiveTime, String> {
+    let error = || "Time must be HH:MM or like 7:30am".to_string();
+    let input = input.trim().to_lowercase().replace('.', "");
+    let (clock, offset) = if let Some(clock) = input.strip_suffix("am") {
+        (clock.trim(), Some(0))
+    } else if let Some(clock) = input.strip_suffix("pm") {
+        (clock.trim(), Some(12))
+    } else {
+        (input.as_str(), None)
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour, minute),
+        // "7am" is clear enough, a bare "7" is not.
+        None if offset.is_some() => (clock, "00"),
+        _ => return Err(error()),
+    };
+    let hour: u32 = hour.parse().map_err(|_| error())?;
+    let minute: u32 = minute.parse().map_err(|_| error())?;
+    let hour = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return Err(error()),
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0).ok_or_else(error)
+}
+
+/// The user-editable part of an alarm.
+pub struct AlarmSpec {
+    pub label: String,
+    pub time: NaiveTime,
+    pub schedule: Schedule,
+    pub sound: Sound,
+    pub volume: Volume,
+    pub rule: Option<Rule>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct AlarmList {
+    alarms: Vec<Alarm>,
+    next_id: u32,
+}
+
+impl AlarmList {
+    pub fn iter(&self) -> impl Iterator<Item = &Alarm> {
+        self.alarms.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.alarms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alarms.is_empty()
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Alarm> {
+        self.alarms.iter().find(|a| a.id == id)
+    }
+
+    fn get_mut(&mut self, id: u32) -> Option<&mut Alarm> {
+        self.alarms.iter_mut().find(|a| a.id == id)
+    }
+
+    pub fn add(&mut self, spec: AlarmSpec, now: DateTime<Local>) -> u32 {
+        self.next_id += 1;
+        let mut alarm = Alarm {
+            id: self.next_id,
+            label: spec.label,
+            enabled: true,
+            time: spec.time,
+            schedule: spec.schedule,
+            sound: spec.sound,
+            volume: spec.volume,
+            rule: spec.rule,
+            adjustment: None,
+            skip: None,
+            due: None,
+            snoozed_until: None,
+            history: Vec::new(),
+        };
+        alarm.rearm(now);
+        self.alarms.push(alarm);
+        self.alarms.sort_by_key(|a| a.time);
+        self.next_id
+    }
+
+    pub fn update(&mut self, id: u32, spec: AlarmSpec, now: DateTime<Local>) {
+        if let Some(alarm) = self.get_mut(id) {
+            alarm.label = spec.label;
+            alarm.time = spec.time;
+            alarm.schedule = spec.schedule;
+            alarm.sound = spec.sound;
+            alarm.volume = spec.volume;
+            alarm.rule = spec.rule;
+            alarm.enabled = true;
+            alarm.skip = None;
+            alarm.snoozed_until = None;
+            alarm.rearm(now);
+        }
+        self.alarms.sort_by_key(|a| a.time);
+    }
+
+    pub fn remove(&mut self, id: u32) {
+        self.alarms.retain(|a| a.id != id);
+    }
+
+    pub fn toggle(&mut self, id: u32, now: DateTime<Local>) {
+        if let Some(alarm) = self.get_mut(id) {
+            alarm.enabled = !alarm.enabled;
+            alarm.snoozed_until = None;
+            alarm.rearm(now);
+        }
+    }
+
+    /// Skips the upcoming occurrence, or un-skips it if already skipped.
+    pub fn toggle_skip(&mut self, id: u32, now: DateTime<Local>) {
+        if let Some(alarm) = self.get_mut(id) {
+            alarm.skip = match alarm.skip {
+                Some(_) => None,
+                None => alarm.due,
+            };
+            alarm.rearm(now);
+        }
+    }
+
+    /// The alarm that rings soonest.
+    pub fn next_due(&self) -> Option<&Alarm> {
+        self.alarms
+            .iter()
+            .filter(|a| a.next_ring().is_some())
+            .min_by_key(|a| a.next_ring())
+    }
+
+    /// Number of alarms waiting to ring.
+    pub fn pending(&self) -> usize {
+        self.alarms
+            .iter()
+            .filter(|a| a.next_ring().is_some())
+            .count()
+    }
+
+    /// Returns the ids of every alarm whose time has come. Recurring alarms
+    /// re-arm for their next occurrence, one-shot alarms switch off.
+    pub fn take_due(&mut self, now: DateTime<Local>) -> Vec<u32> {
+        let mut fired = Vec::new();
+        for alarm in &mut self.alarms {
+            if alarm.snoozed_until.is_some_and(|at| at <= now) {
+                alarm.snoozed_until = None;
+                fired.push(alarm.id);
+            }
+            if let Some(due) = alarm.due
+                && alarm.adjusted_due().is_some_and(|at| at <= now)
+            {
+                if !alarm.schedule.is_recurring() {
+                    alarm.enabled = false;
+                }
+                // An early ring still uses up the scheduled one.
+                alarm.rearm(now.max(due));
+                if !fired.contains(&alarm.id) {
+                    fired.push(alarm.id);
+                }
+            }
+        }
+        fired
+    }
+
+    /// Lets the rings weather rules skip go by, recording them. Returns
+    /// whether there were any.
+    pub fn pass_skipped(&mut self, now: DateTime<Local>) -> bool {
+        let mut passed = false;
+        for alarm in &mut self.alarms {
+            if let Some(due) = alarm.due.filter(|due| *due <= now)
+                && alarm.skips_due()
+            {
+                alarm.push_history(due, Outcome::Skipped);
+                if !alarm.schedule.is_recurring() {
+                    alarm.enabled = false;
+                }
+                alarm.rearm(now);
+                passed = true;
+            }
+        }
+        passed
+    }
+
+    /// Runs the weather rules of alarms coming up within the hour. Returns
+    /// whether any decision changed.
+    pub fn apply_weather(&mut self, now: DateTime<Local>, report: &Report, units: Units) -> bool {
+        let mut changed = false;
+        for alarm in &mut self.alarms {
+            let (Some(rule), Some(due)) = (&alarm.rule, alarm.due) else {
+                continue;
+            };
+            // Once the earliest ring time has come the decision stands.
+            let decide_by = due - rule.lead();
+            if now < decide_by - EVALUATE_AHEAD || now >= decide_by {
+                continue;
+            }
+            let adjustment = rule.evaluate(report, due, units);
+            if adjustment != alarm.adjustment {
+                alarm.adjustment = adjustment;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Records how a ring ended, snoozing the alarm if asked to.
+    pub fn record(&mut self, id: u32, outcome: Outcome, now: DateTime<Local>, snooze: TimeDelta) {
+        if let Some(alarm) = self.get_mut(id) {
+            if outcome == Outcome::Snoozed {
+                alarm.snoozed_until = Some(now + snooze);
+            }
+            alarm.push_history(now, outcome);
+        }
+    }
+
+    /// Stops the rings nobody reacted to within `timeout`, recording them
+    /// as missed. Returns whether there were any.
+    pub fn time_out(
+        &mut self,
+        ringing: &mut Vec<Ringing>,
+        now: DateTime<Local>,
+        timeout: TimeDelta,
+    ) -> bool {
+        let before = ringing.len();
+        ringing.retain(|ringing| {
+            let expired = now - ringing.since >= timeout;
+            if expired {
+                self.record(ringing.alarm_id, Outcome::Missed, now, TimeDelta::zero());
+            }
+            !expired
+        });
+        ringing.len() != before
+    }
+
+    /// Brings freshly loaded alarms up to date, recording every ring that
+    /// came due while the app was not running as missed.
+    pub fn catch_up(&mut self, now: DateTime<Local>) {
+        for alarm in &mut self.alarms {
+            if let Some(at) = alarm.snoozed_until.take_if(|at| *at <= now) {
+                alarm.push_history(at, Outcome::Missed);
+            }
+            while let Some(due) = alarm.due.filter(|due| *due <= now) {
+                let outcome = if alarm.skips_due() {
+                    Outcome::Skipped
+                } else {
+                    Outcome::Missed
+                };
+                alarm.push_history(due, outcome);
+                if !alarm.schedule.is_recurring() {
+                    alarm.enabled = false;
+                }
+                alarm.rearm(due);
+            }
+            alarm.rearm(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::Sky;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn daily_at_seven() -> AlarmSpec {
+        AlarmSpec {
+            label: String::new(),
+            time: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+            schedule: Schedule::Daily,
+            sound: Sound::default(),
+            volume: Volume::default(),
+            rule: None,
+        }
+    }
+
+    /// A report of the weather now, without a forecast.
+    fn weather(sky: Sky, temperature: f64) -> Report {
+        crate::weather::tests::report(sky, temperature, &[])
+    }
+
+    fn with_rule(rule: &str) -> AlarmSpec {
+        AlarmSpec {
+            rule: Rule::parse(rule).unwrap(),
+            ..daily_at_seven()
+        }
+    }
+
+    #[test]
+    fn decides_within_the_hour_before_the_earliest_ring() {
+        let mut alarms = AlarmList::default();
+        let id = alarms.add(with_rule("20m earlier if snow"), at(15, 22, 0));
+        let snow = weather(Sky::Snow, -1.0);
+
+        // Decided between 05:40 and 06:40, an hour before the earliest ring.
+        assert!(!alarms.apply_weather(at(16, 5, 39), &snow, Units::Metric));
+        assert!(alarms.apply_weather(at(16, 5, 40), &snow, Units::Metric));
+        assert!(!alarms.apply_weather(at(16, 6, 0), &snow, Units::Metric));
+        let alarm = alarms.get(id).unwrap();
+        assert_eq!(alarm.adjusted_due(), Some(at(16, 6, 40)));
+        assert_eq!(alarm.next_ring(), Some(at(16, 6, 40)));
+
+        // A change of forecast in time is taken back, a late one is not.
+        let clear = weather(Sky::Clear, 4.0);
+        assert!(alarms.apply_weather(at(16, 6, 20), &clear, Units::Metric));
+        assert!(alarms.apply_weather(at(16, 6, 30), &snow, Units::Metric));
+        assert!(!alarms.apply_weather(at(16, 6, 40), &clear, Units::Metric));
+
+        assert!(alarms.take_due(at(16, 6, 39)).is_empty());
+        assert_eq!(alarms.take_due(at(16, 6, 40)), [id]);
+        let alarm = alarms.get(id).unwrap();
+        assert_eq!(alarm.adjustment, None);
+        assert_eq!(alarm.due, Some(at(17, 7, 0)));
+    }
+
+    #[test]
+    fn records_skipped_rings() {
+        let mut alarms = AlarmList::default();
+        let id = alarms.add(with_rule("skip if rain"), at(15, 22, 0));
+        assert!(alarms.apply_weather(at(16, 6, 30), &weather(Sky::Rain, 9.0), Units::Metric));
+        assert_eq!(alarms.get(id).unwrap().next_ring(), None);
+
+        assert!(!alarms.pass_skipped(at(16, 6, 59)));
+        assert!(alarms.take_due(at(16, 7, 0)).is_empty());
+        assert!(alarms.pass_skipped(at(16, 7, 0)));
+        let alarm = alarms.get(id).unwrap();
+        let ring = alarm.last_ring().unwrap();
+        assert_eq!((ring.at, ring.outcome), (at(16, 7, 0), Outcome::Skipped));
+        assert_eq!(alarm.adjustment, None);
+        assert_eq!(alarm.due, Some(at(17, 7, 0)));
+        assert!(!alarms.pass_skipped(at(16, 7, 1)));
+    }
+
+    #[test]
+    fn parses_24_and_12_hour_times() {
+        let time = |hour, minute| Ok(NaiveTime::from_hms_opt(hour, minute, 0).unwrap());
+        assert_eq!(parse_time("19:30"), time(19, 30));
+        assert_eq!(parse_time(" 07:05 "), time(7, 5));
+        assert_eq!(parse_time("7:30am"), time(7, 30));
+        assert_eq!(parse_time("7 pm"), time(19, 0));
+        assert_eq!(parse_time("7:30 P.M."), time(19, 30));
+        assert_eq!(parse_time("12am"), time(0, 0));
+        assert_eq!(parse_time("12pm"), time(12, 0));
+        assert_eq!(parse_time("12:15am"), time(0, 15));
+        for invalid in ["13:00pm", "0am", "7:5am", "7", "24:00", "7:60", "seven am"] {
+            assert_eq!(
+                parse_time(invalid),
+                Err("Time must be HH:MM or like 7:30am".to_string()),
+                "{invalid}"
+            );
+        }
+    }
+
+    fn history(alarm: &Alarm) -> Vec<(DateTime<Local>, Outcome)> {
+        alarm
+            .history
+            .iter()
+            .map(|ring| (ring.at, ring.outcome))
+            .collect()
+    }
+
+    #[test]
+    fn rings_again_after_a_snooze() {
+        let mut alarms = AlarmList::default();
+        let id = alarms.add(daily_at_seven(), at(15, 22, 0));
+        assert_eq!(alarms.take_due(at(16, 7, 0)), [id]);
+        alarms.record(id, Outcome::Snoozed, at(16, 7, 1), TimeDelta::minutes(9));
+
+        let alarm = alarms.get(id).unwrap();
+        assert_eq!(alarm.next_ring(), Some(at(16, 7, 10)));
+        assert_eq!(alarm.due, Some(at(17, 7, 0)));
+        assert_eq!(history(alarm), [(at(16, 7, 1), Outcome::Snoozed)]);
+        assert!(alarms.take_due(at(16, 7, 9)).is_empty());
+        assert_eq!(alarms.take_due(at(16, 7, 10)), [id]);
+        assert!(alarms.take_due(at(16, 7, 11)).is_empty());
+
+        alarms.record(id, Outcome::Dismissed, at(16, 7, 12), TimeDelta::minutes(9));
+        let alarm = alarms.get(id).unwrap();
+        assert_eq!(alarm.snoozed_until, None);
+        assert_eq!(alarm.next_ring(), Some(at(17, 7, 0)));
+    }
+
+    #[test]
+    fn counts_unanswered_rings_as_missed() {
+        let mut alarms = AlarmList::default();
+        let id = alarms.add(daily_at_seven(), at(15, 22, 0));
+        let mut ringing = vec![Ringing {
+            alarm_id: id,
+            since: at(16, 7, 0),
+        }];
+        let timeout = TimeDelta::minutes(15);
+        assert!(!alarms.time_out(&mut ringing, at(16, 7, 14), timeout));
+        assert_eq!(ringing.len(), 1);
+        assert!(alarms.time_out(&mut ringing, at(16, 7, 15), timeout));
+        assert!(ringing.is_empty());
+        let alarm = alarms.get(id).unwrap();
+        assert_eq!(history(alarm), [(at(16, 7, 15), Outcome::Missed)]);
+        assert_eq!(alarm.snoozed_until, None);
+    }
+
+    #[test]
+    fn records_rings_missed_while_closed() {
+        let mut alarms = AlarmList::default();
+        let daily = alarms.add(daily_at_seven(), at(15, 22, 0));
+        let once = alarms.add(
+            AlarmSpec {
+                schedule: Schedule::Once,
+                ..daily_at_seven()
+            },
+            at(15, 22, 0),
+        );
+        let snoozed = alarms.add(
+            AlarmSpec {
+                time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+                ..daily_at_seven()
+            },
+            at(15, 22, 0),
+        );
+        let rainy = alarms.add(with_rule("skip if rain"), at(15, 22, 0));
+        alarms.apply_weather(at(16, 6, 30), &weather(Sky::Rain, 9.0), Units::Metric);
+        assert_eq!(alarms.take_due(at(16, 6, 0)), [snoozed]);
+        let nine_minutes = TimeDelta::minutes(9);
+        alarms.record(snoozed, Outcome::Snoozed, at(16, 6, 25), nine_minutes);
+        assert!(alarms.take_due(at(16, 6, 30)).is_empty());
+
+        // Closed from 06:30 until two days later.
+        alarms.catch_up(at(18, 9, 0));
+        let daily = alarms.get(daily).unwrap();
+        assert_eq!(
+            history(daily),
+            [
+                (at(16, 7, 0), Outcome::Missed),
+                (at(17, 7, 0), Outcome::Missed),
+                (at(18, 7, 0), Outcome::Missed)
+            ]
+        );
+        assert_eq!(daily.due, Some(at(19, 7, 0)));
+        let once = alarms.get(once).unwrap();
+        assert_eq!(history(once), [(at(16, 7, 0), Outcome::Missed)]);
+        assert!(!once.enabled);
+        assert_eq!(once.due, None);
+        let snoozed = alarms.get(snoozed).unwrap();
+        assert_eq!(
+            history(snoozed),
+            [
+                (at(16, 6, 25), Outcome::Snoozed),
+                (at(16, 6, 34), Outcome::Missed),
+                (at(17, 6, 0), Outcome::Missed),
+                (at(18, 6, 0), Outcome::Missed)
+            ]
+        );
+        assert_eq!(snoozed.snoozed_until, None);
+        assert_eq!(snoozed.due, Some(at(19, 6, 0)));
+        let rainy = alarms.get(rainy).unwrap();
+        // Only the ring the forecast was for is skipped.
+        assert_eq!(
+            history(rainy),
+            [
+                (at(16, 7, 0), Outcome::Skipped),
+                (at(17, 7, 0), Outcome::Missed),
+                (at(18, 7, 0), Outcome::Missed)
+            ]
+        );
+        assert_eq!(rainy.adjustment, None);
+    }
+
+    #[test]
+    fn skips_the_next_ring_only() {
+        let mut alarms = AlarmList::default();
+        let id = alarms.add(daily_at_seven(), at(15, 8, 0));
+        assert_eq!(alarms.get(id).unwrap().due, Some(at(16, 7, 0)));
+
+        alarms.toggle_skip(id, at(15, 9, 0));
+        assert_eq!(alarms.get(id).unwrap().due, Some(at(17, 7, 0)));
+        alarms.toggle_skip(id, at(15, 9, 0));
+        assert_eq!(alarms.get(id).unwrap().due, Some(at(16, 7, 0)));
+
+        alarms.toggle_skip(id, at(15, 9, 0));
+        assert!(alarms.take_due(at(16, 7, 0)).is_empty());
+        assert_eq!(alarms.take_due(at(17, 7, 0)), [id]);
+        // The skip was used up, so the day after rings again.
+        let alarm = alarms.get(id).unwrap();
+        assert_eq!(alarm.skip, None);
+        assert_eq!(alarm.due, Some(at(18, 7, 0)));
+    }
+}
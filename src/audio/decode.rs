@@ -0,0 +1,218 @@
+use super::{CHANNELS, SAMPLE_RATE, Source};
+use anyhow::{Context, Result};
+use std::{fs::File, io::ErrorKind, path::Path};
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{CODEC_TYPE_NULL, Decoder, DecoderOptions},
+    errors::Error,
+    formats::{FormatOptions, FormatReader},
+    io::{MediaSource, MediaSourceStream},
+    meta::MetadataOptions,
+    probe::Hint,
+};
+
+/// Decodes any format Symphonia knows about into the player format.
+pub struct DecoderSource {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    resampler: Option<Resampler>,
+    scratch: Vec<f32>,
+}
+
+impl DecoderSource {
+    pub fn open(path: &Path) -> Result<DecoderSource> {
+        let file = File::open(path).with_context(|| format!("cannot open {}", path.display()))?;
+        let mut hint = Hint::new();
+        if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+            hint.with_extension(ext);
+        }
+        DecoderSource::new(Box::new(file), &hint)
+            .with_context(|| format!("cannot play {}", path.display()))
+    }
+
+    pub fn new(source: Box<dyn MediaSource>, hint: &Hint) -> Result<DecoderSource> {
+        let stream = MediaSourceStream::new(source, Default::default());
+        let probed = symphonia::default::get_probe().format(
+            hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )?;
+        let format = probed.format;
+        let track = format
+            .tracks()
+            .iter()
+            .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+            .context("no audio track")?;
+        let decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())?;
+        Ok(DecoderSource {
+            track_id: track.id,
+            format,
+            decoder,
+            resampler: None,
+            scratch: Vec::new(),
+        })
+    }
+}
+
+impl Source for DecoderSource {
+    fn fill(&mut self, buf: &mut Vec<f32>) -> Result<bool> {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(Error::IoError(err)) if err.kind() == ErrorKind::UnexpectedEof => {
+                    return Ok(false);
+                }
+                Err(Error::ResetRequired) => {
+                    self.decoder.reset();
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // A corrupt frame is skipped rather than ending playback.
+                Err(Error::DecodeError(_)) => continue,
+                Err(err) => return Err(err.into()),
+            };
+            let spec = *decoded.spec();
+            let channels = spec.channels.count();
+            if decoded.frames() == 0 || channels == 0 {
+                continue;
+            }
+            let mut samples = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+            samples.copy_interleaved_ref(decoded);
+
+            // Mono is duplicated, anything wider keeps its first two channels.
+            self.scratch.clear();
+            for frame in samples.samples().chunks_exact(channels) {
+                let left = frame[0];
+                let right = if channels > 1 { frame[1] } else { left };
+                self.scratch.extend([left, right]);
+            }
+
+            if spec.rate == SAMPLE_RATE {
+                buf.extend_from_slice(&self.scratch);
+            } else {
+                self.resampler
+                    .get_or_insert_with(|| Resampler::new(spec.rate))
+                    .process(&self.scratch, buf);
+            }
+            return Ok(true);
+        }
+    }
+}
+
+/// Linear-interpolating sample rate converter for stereo frames.
+struct Resampler {
+    /// Input frames per output frame.
+    step: f64,
+    /// Position of the next output frame relative to the start of the
+    /// current input block; `-1.0` is the last frame of the previous block.
+    pos: f64,
+    last: [f32; CHANNELS],
+}
+
+impl Resampler {
+    fn new(rate: u32) -> Resampler {
+        Resampler {
+            step: f64::from(rate) / f64::from(SAMPLE_RATE),
+            pos: 0.0,
+            last: [0.0; CHANNELS],
+        }
+    }
+
+    fn process(&mut self, input: &[f32], out: &mut Vec<f32>) {
+        let frames = input.len() / CHANNELS;
+        if frames == 0 {
+            return;
+        }
+        let frame = |index: isize| -> [f32; CHANNELS] {
+            if index < 0 {
+                self.last
+            } else {
+                let start = index as usize * CHANNELS;
+                [input[start], input[start + 1]]
+            }
+        };
+
+        while self.pos < (frames - 1) as f64 {
+            let index = self.pos.floor();
+            let t = (self.pos - index) as f32;
+            let (a, b) = (frame(index as isize), frame(index as isize + 1));
+            out.extend([a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t]);
+            self.pos += self.step;
+        }
+        self.pos -= frames as f64;
+        self.last = frame(frames as isize - 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    /// A mono 16-bit WAV file holding `samples` at `rate`.
+    fn wav(rate: u32, samples: &[i16]) -> Vec<u8> {
+        let data = (samples.len() * 2) as u32;
+        let mut out = Vec::new();
+        out.extend(b"RIFF");
+        out.extend((36 + data).to_le_bytes());
+        out.extend(b"WAVEfmt ");
+        out.extend(16u32.to_le_bytes());
+        out.extend(1u16.to_le_bytes());
+        out.extend(1u16.to_le_bytes());
+        out.extend(rate.to_le_bytes());
+        out.extend((rate * 2).to_le_bytes());
+        out.extend(2u16.to_le_bytes());
+        out.extend(16u16.to_le_bytes());
+        out.extend(b"data");
+        out.extend(data.to_le_bytes());
+        for sample in samples {
+            out.extend(sample.to_le_bytes());
+        }
+        out
+    }
+
+    #[test]
+    fn decodes_mono_into_stereo_at_the_player_rate() {
+        let path = env::temp_dir().join(format!("clockradio-{}-tone.wav", std::process::id()));
+        fs::write(&path, wav(22_050, &[16_384; 22_050])).unwrap();
+        let mut source = DecoderSource::open(&path).unwrap();
+        let mut buf = Vec::new();
+        while source.fill(&mut buf).unwrap() {}
+        fs::remove_file(&path).unwrap();
+
+        // One second of input is one second of output, give or take the
+        // last frame the interpolation has nothing to blend with.
+        assert_eq!(buf.len() % CHANNELS, 0);
+        let frames = buf.len() / CHANNELS;
+        assert!((44_098..=44_100).contains(&frames), "{frames} frames");
+        assert!(buf.iter().all(|sample| (sample - 0.5).abs() < 1e-4));
+        // The end of the file stays the end.
+        assert!(!source.fill(&mut buf).unwrap());
+        assert_eq!(buf.len(), frames * CHANNELS);
+    }
+
+    #[test]
+    fn interpolates_across_blocks() {
+        let mut resampler = Resampler::new(SAMPLE_RATE / 2);
+        let ramp: Vec<f32> = (0..8).flat_map(|n| [n as f32, -(n as f32)]).collect();
+        let mut out = Vec::new();
+        resampler.process(&ramp[..8], &mut out);
+        resampler.process(&ramp[8..], &mut out);
+
+        let left: Vec<f32> = out.iter().step_by(2).copied().collect();
+        let right: Vec<f32> = out.iter().skip(1).step_by(2).copied().collect();
+        let expected: Vec<f32> = (0..14).map(|n| n as f32 / 2.0).collect();
+        assert_eq!(left, expected);
+        assert_eq!(right, expected.iter().map(|n| -n).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,276 @@
+//! Sound for alarms and the radio: decoding, synthesis and playback.
+//!
+//! Everything is converted to interleaved stereo `f32` at [`SAMPLE_RATE`]
+//! and handed to an output [`Sink`] on a dedicated thread.
+
+mod decode;
+mod output;
+mod ramp;
+mod tone;
+
+pub use decode::DecoderSource;
+pub use output::{Output, OutputConfig, open_output};
+pub use ramp::{Ramp, Volume};
+
+use crate::{playlist, radio};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU32, Ordering},
+    },
+    thread::{self, JoinHandle},
+};
+use tone::Tone;
+
+pub const SAMPLE_RATE: u32 = 44_100;
+pub const CHANNELS: usize = 2;
+
+/// Produces interleaved stereo samples at [`SAMPLE_RATE`].
+pub trait Source: Send {
+    /// Appends the next block of samples to `buf`. Returns `false` once the
+    /// source is exhausted.
+    fn fill(&mut self, buf: &mut Vec<f32>) -> Result<bool>;
+}
+
+/// Consumes interleaved stereo samples at [`SAMPLE_RATE`].
+pub trait Sink: Send {
+    fn write(&mut self, samples: &[f32]) -> Result<()>;
+}
+
+/// What an alarm plays when it rings.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sound {
+    /// The built-in beeping tone.
+    #[default]
+    Tone,
+    /// A WAV, FLAC, Ogg Vorbis or MP3 file, played on repeat.
+    File(PathBuf),
+    /// An M3U or PLS playlist of local files, played in order on repeat.
+    Playlist(PathBuf),
+    /// A radio station preset, by slot.
+    Preset(usize),
+}
+
+impl Sound {
+    /// Parses the editor syntax: `tone` (or nothing), `preset <key>`, or a
+    /// path to a sound file or playlist.
+    pub fn parse(input: &str) -> Result<Sound, String> {
+        let input = input.trim();
+        if input.is_empty() || input.eq_ignore_ascii_case("tone") {
+            return Ok(Sound::Tone);
+        }
+        if let Some(key) = input.strip_prefix("preset") {
+            let mut chars = key.trim().chars();
+            return match (chars.next().and_then(radio::slot_for_key), chars.next()) {
+                (Some(slot), None) => Ok(Sound::Preset(slot)),
+                _ => Err("Presets are numbered 1 to 9 and 0".to_string()),
+            };
+        }
+        let path = expand_home(input);
+        if !path.is_file() {
+            return Err(format!("No such file \"{input}\""));
+        }
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        if ["m3u", "m3u8", "pls"].contains(&extension.to_ascii_lowercase().as_str()) {
+            Ok(Sound::Playlist(path))
+        } else {
+            Ok(Sound::File(path))
+        }
+    }
+}
+
+impl fmt::Display for Sound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sound::Tone => write!(f, "tone"),
+            Sound::File(path) | Sound::Playlist(path) => write!(f, "{}", path.display()),
+            Sound::Preset(slot) => write!(f, "preset {}", radio::key_for_slot(*slot)),
+        }
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Source for an alarm sound at the alarm's volume. Files that cannot be
+/// opened or stop decoding fall back to the built-in tone so an alarm is
+/// never silent.
+///
+/// Stations are streamed by [`radio::Radio`] instead; a preset passed here
+/// plays the tone.
+pub fn alarm_source(sound: &Sound, volume: Volume) -> Box<dyn Source> {
+    let files = match sound {
+        Sound::Tone | Sound::Preset(_) => vec![],
+        Sound::File(path) => vec![path.clone()],
+        Sound::Playlist(path) => playlist::files(path).unwrap_or_default(),
+    };
+    let source: Box<dyn Source> = if files.is_empty() {
+        Box::new(Tone::default())
+    } else {
+        Box::new(Fallback::new(Box::new(Repeat::new(files))))
+    };
+    Box::new(Ramp::new(source, volume))
+}
+
+/// Plays files in order, over and over. Files that fail are skipped.
+struct Repeat {
+    files: Vec<PathBuf>,
+    next: usize,
+    current: Option<DecoderSource>,
+    /// Files opened since audio was last produced.
+    silent: usize,
+}
+
+impl Repeat {
+    fn new(files: Vec<PathBuf>) -> Repeat {
+        Repeat {
+            files,
+            next: 0,
+            current: None,
+            silent: 0,
+        }
+    }
+}
+
+impl Source for Repeat {
+    fn fill(&mut self, buf: &mut Vec<f32>) -> Result<bool> {
+        loop {
+            // A list where nothing plays would spin forever.
+            if self.silent > self.files.len() {
+                anyhow::bail!("no playable audio in {} files", self.files.len());
+            }
+            let source = match &mut self.current {
+                Some(source) => source,
+                None => {
+                    let path = &self.files[self.next % self.files.len()];
+                    self.next = (self.next + 1) % self.files.len();
+                    self.silent += 1;
+                    match DecoderSource::open(path) {
+                        Ok(source) => self.current.insert(source),
+                        Err(_) => continue,
+                    }
+                }
+            };
+            match source.fill(buf) {
+                Ok(true) => {
+                    self.silent = 0;
+                    return Ok(true);
+                }
+                Ok(false) | Err(_) => self.current = None,
+            }
+        }
+    }
+}
+
+/// Switches to the built-in tone when the primary source fails.
+struct Fallback {
+    primary: Option<Box<dyn Source>>,
+    tone: Tone,
+}
+
+impl Fallback {
+    fn new(primary: Box<dyn Source>) -> Fallback {
+        Fallback {
+            primary: Some(primary),
+            tone: Tone::default(),
+        }
+    }
+}
+
+impl Source for Fallback {
+    fn fill(&mut self, buf: &mut Vec<f32>) -> Result<bool> {
+        if let Some(primary) = &mut self.primary {
+            match primary.fill(buf) {
+                Ok(more) => return Ok(more),
+                Err(_) => self.primary = None,
+            }
+        }
+        self.tone.fill(buf)
+    }
+}
+
+/// A source playing into a sink on its own thread until stopped or exhausted.
+pub struct Player {
+    stop: Arc<AtomicBool>,
+    /// Linear gain as `f32` bits.
+    volume: Arc<AtomicU32>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Player {
+    pub fn start(mut source: Box<dyn Source>, mut sink: Box<dyn Sink>) -> Player {
+        let stop = Arc::new(AtomicBool::new(false));
+        let volume = Arc::new(AtomicU32::new(1.0f32.to_bits()));
+        let stopped = stop.clone();
+        let target = volume.clone();
+        let thread = thread::spawn(move || {
+            let mut buf = Vec::new();
+            let mut gain = 1.0;
+            while !stopped.load(Ordering::Relaxed) {
+                buf.clear();
+                let more = source.fill(&mut buf).unwrap_or(false);
+                let next = f32::from_bits(target.load(Ordering::Relaxed));
+                apply_gain(&mut buf, gain, next);
+                gain = next;
+                if sink.write(&buf).is_err() || !more {
+                    break;
+                }
+            }
+        });
+        Player {
+            stop,
+            volume,
+            thread: Some(thread),
+        }
+    }
+
+    /// Sets the linear gain, from 0.0 for silence to 1.0 for full volume.
+    pub fn set_volume(&self, volume: f32) {
+        self.volume
+            .store(volume.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+
+    /// Asks the thread to stop without waiting for it, for sources that
+    /// may be stuck in a network wait.
+    pub fn detach(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.thread = None;
+    }
+}
+
+/// Scales a block of samples, gliding from gain `from` to `to` across it so
+/// volume changes do not click.
+fn apply_gain(buf: &mut [f32], from: f32, to: f32) {
+    if from == 1.0 && to == 1.0 {
+        return;
+    }
+    let frames = (buf.len() / CHANNELS).max(1) as f32;
+    for (index, frame) in buf.chunks_mut(CHANNELS).enumerate() {
+        let gain = from + (to - from) * (index as f32 / frames);
+        for sample in frame {
+            *sample *= gain;
+        }
+    }
+}
+
+impl Drop for Player {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
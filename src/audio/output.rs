@@ -0,0 +1,234 @@
+use super::{CHANNELS, SAMPLE_RATE, Sink};
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::{
+    env,
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The `[audio]` section of the config file.
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    pub output: Output,
+    /// Shell command reading raw PCM from stdin, for `output = "command"`.
+    pub command: Option<String>,
+    /// Destination for `output = "file"`.
+    pub file: Option<PathBuf>,
+    /// Whether `output = "file"` takes as long as playing out loud would.
+    /// Off renders as fast as the sound can be decoded.
+    pub realtime: bool,
+}
+
+impl Default for OutputConfig {
+    fn default() -> OutputConfig {
+        OutputConfig {
+            output: Output::Auto,
+            command: None,
+            file: None,
+            realtime: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Output {
+    /// The first of pw-play, paplay or aplay found on `$PATH`.
+    #[default]
+    Auto,
+    Command,
+    /// Raw PCM written to a file in real time, for headless testing.
+    File,
+    /// Terminal bell only.
+    None,
+}
+
+/// Players that accept signed 16-bit little-endian stereo on stdin.
+const PLAYERS: [(&str, &str); 3] = [
+    (
+        "pw-play",
+        "pw-play --rate 44100 --channels 2 --format s16 -",
+    ),
+    (
+        "paplay",
+        "paplay --raw --rate=44100 --channels=2 --format=s16le",
+    ),
+    ("aplay", "aplay -q -t raw -f S16_LE -r 44100 -c 2"),
+];
+
+/// Opens the configured output, or `None` if sound is disabled or no
+/// player is installed.
+pub fn open_output(config: &OutputConfig) -> Result<Option<Box<dyn Sink>>> {
+    match config.output {
+        Output::None => Ok(None),
+        Output::File => {
+            let path = config
+                .file
+                .as_ref()
+                .context("output = \"file\" needs a file path")?;
+            Ok(Some(Box::new(FileSink::create(path, config.realtime)?)))
+        }
+        Output::Command => {
+            let Some(command) = &config.command else {
+                bail!("output = \"command\" needs a command");
+            };
+            Ok(Some(Box::new(CommandSink::spawn(command)?)))
+        }
+        Output::Auto => match PLAYERS.iter().find(|(program, _)| on_path(program)) {
+            Some((_, command)) => Ok(Some(Box::new(CommandSink::spawn(command)?))),
+            None => Ok(None),
+        },
+    }
+}
+
+fn on_path(program: &str) -> bool {
+    env::var_os("PATH")
+        .is_some_and(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+fn encode(samples: &[f32], bytes: &mut Vec<u8>) {
+    bytes.clear();
+    for sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16;
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// Pipes PCM into an external player.
+struct CommandSink {
+    child: Child,
+    stdin: ChildStdin,
+    bytes: Vec<u8>,
+}
+
+impl CommandSink {
+    fn spawn(command: &str) -> Result<CommandSink> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("cannot run {command}"))?;
+        let stdin = child.stdin.take().context("player has no stdin")?;
+        Ok(CommandSink {
+            child,
+            stdin,
+            bytes: Vec::new(),
+        })
+    }
+}
+
+impl Sink for CommandSink {
+    fn write(&mut self, samples: &[f32]) -> Result<()> {
+        encode(samples, &mut self.bytes);
+        self.stdin.write_all(&self.bytes)?;
+        Ok(())
+    }
+}
+
+impl Drop for CommandSink {
+    fn drop(&mut self) {
+        // Stop at once instead of draining what the player has buffered.
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Writes raw signed 16-bit little-endian stereo PCM at [`SAMPLE_RATE`],
+/// paced like a sound card so playback takes as long as it would out loud.
+/// Every sound is appended, so one file records a whole session.
+struct FileSink {
+    file: BufWriter<File>,
+    realtime: bool,
+    started: Instant,
+    frames: u64,
+    bytes: Vec<u8>,
+}
+
+impl FileSink {
+    fn create(path: &Path, realtime: bool) -> Result<FileSink> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("cannot open {}", path.display()))?;
+        Ok(FileSink {
+            file: BufWriter::new(file),
+            realtime,
+            started: Instant::now(),
+            frames: 0,
+            bytes: Vec::new(),
+        })
+    }
+}
+
+impl Sink for FileSink {
+    fn write(&mut self, samples: &[f32]) -> Result<()> {
+        encode(samples, &mut self.bytes);
+        self.file.write_all(&self.bytes)?;
+        self.file.flush()?;
+        if !self.realtime {
+            return Ok(());
+        }
+
+        self.frames += (samples.len() / CHANNELS) as u64;
+        let played = Duration::from_secs_f64(self.frames as f64 / f64::from(SAMPLE_RATE));
+        if let Some(ahead) = played.checked_sub(self.started.elapsed()) {
+            thread::sleep(ahead);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{Source, tone::Tone};
+    use std::fs;
+
+    fn samples(path: &Path) -> Vec<i16> {
+        fs::read(path)
+            .unwrap()
+            .chunks(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+            .collect()
+    }
+
+    #[test]
+    fn appends_each_sound_to_the_file() {
+        let path = env::temp_dir().join(format!("clockradio-{}.pcm", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let config = OutputConfig {
+            output: Output::File,
+            command: None,
+            file: Some(path.clone()),
+            realtime: false,
+        };
+        let mut tone = Tone::default();
+        let mut buf = Vec::new();
+        tone.fill(&mut buf).unwrap();
+        for _ in 0..2 {
+            let mut sink = open_output(&config).unwrap().unwrap();
+            sink.write(&buf).unwrap();
+        }
+
+        let written = samples(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(written.len(), 2 * buf.len());
+        let expected: Vec<i16> = buf
+            .iter()
+            .map(|sample| (sample * f32::from(i16::MAX)) as i16)
+            .collect();
+        assert_eq!(written[..buf.len()], expected);
+        assert_eq!(written[buf.len()..], expected);
+        assert!(written.iter().any(|&sample| sample > 8000));
+    }
+}
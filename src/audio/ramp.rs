@@ -0,0 +1,308 @@
+use super::{CHANNELS, SAMPLE_RATE, Source};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Steps of a [`Curve::Stepped`] ramp.
+const STEPS: u32 = 5;
+/// Where an exponential ramp starts, relative to the target (-60 dB).
+const EXP_FLOOR_DB: f32 = -60.0;
+/// Longest ramp, an hour.
+const MAX_RAMP_SECS: u32 = 60 * 60;
+
+/// How loud an alarm gets and how it gets there.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Volume {
+    /// Final volume in percent.
+    pub target: u8,
+    /// Seconds from silence to the target, 0 to start at full volume.
+    pub ramp_secs: u32,
+    pub curve: Curve,
+}
+
+impl Default for Volume {
+    fn default() -> Volume {
+        Volume {
+            target: 100,
+            ramp_secs: 0,
+            curve: Curve::Linear,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Curve {
+    #[default]
+    Linear,
+    /// Even steps in loudness rather than amplitude; stays quiet for longer.
+    Exponential,
+    /// Jumps up in five equal steps.
+    Stepped,
+}
+
+impl Curve {
+    fn as_str(self) -> &'static str {
+        match self {
+            Curve::Linear => "linear",
+            Curve::Exponential => "exp",
+            Curve::Stepped => "stepped",
+        }
+    }
+
+    /// Gain at `progress` through the ramp, both from 0.0 to 1.0.
+    fn gain(self, progress: f32) -> f32 {
+        match self {
+            Curve::Linear => progress,
+            Curve::Exponential if progress <= 0.0 => 0.0,
+            Curve::Exponential => 10f32.powf(EXP_FLOOR_DB * (1.0 - progress) / 20.0),
+            Curve::Stepped => {
+                ((progress * STEPS as f32).floor() + 1.0).min(STEPS as f32) / STEPS as f32
+            }
+        }
+    }
+}
+
+impl Volume {
+    /// Parses the editor syntax: a percentage, optionally followed by a
+    /// ramp time and curve, as in `80% over 2m exp`. Empty is full volume.
+    pub fn parse(input: &str) -> Result<Volume, String> {
+        let mut volume = Volume::default();
+        for word in input.to_ascii_lowercase().split_whitespace() {
+            let percent = word.strip_suffix('%').unwrap_or(word);
+            if percent.bytes().all(|b| b.is_ascii_digit()) {
+                volume.target = match percent.parse() {
+                    Ok(target @ 1..=100) => target,
+                    _ => return Err("Volume must be 1% to 100%".to_string()),
+                };
+            } else if let Some(secs) = parse_duration(word) {
+                volume.ramp_secs = secs;
+            } else {
+                volume.curve = match word {
+                    "over" => continue,
+                    "linear" | "lin" => Curve::Linear,
+                    "exp" | "exponential" => Curve::Exponential,
+                    "stepped" | "steps" | "step" => Curve::Stepped,
+                    _ => return Err(format!("Unknown volume setting \"{word}\"")),
+                };
+            }
+        }
+        Ok(volume)
+    }
+
+    /// Gain after `frame` frames of playback.
+    fn gain(&self, frame: u64) -> f32 {
+        let target = f32::from(self.target) / 100.0;
+        let ramp_frames = u64::from(self.ramp_secs) * u64::from(SAMPLE_RATE);
+        if frame >= ramp_frames {
+            return target;
+        }
+        target * self.curve.gain(frame as f32 / ramp_frames as f32)
+    }
+}
+
+/// `30s`, `2m` or `1m30s`, up to [`MAX_RAMP_SECS`].
+fn parse_duration(word: &str) -> Option<u32> {
+    let (minutes, seconds) = match word.split_once('m') {
+        Some((minutes, rest)) => (minutes.parse::<u32>().ok()?, rest),
+        None => (0, word),
+    };
+    let seconds = match seconds {
+        "" => 0,
+        seconds => seconds.strip_suffix('s')?.parse::<u32>().ok()?,
+    };
+    minutes
+        .checked_mul(60)?
+        .checked_add(seconds)
+        .filter(|secs| *secs <= MAX_RAMP_SECS)
+}
+
+impl fmt::Display for Volume {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.target)?;
+        if self.ramp_secs > 0 {
+            match (self.ramp_secs / 60, self.ramp_secs % 60) {
+                (0, seconds) => write!(f, " over {seconds}s")?,
+                (minutes, 0) => write!(f, " over {minutes}m")?,
+                (minutes, seconds) => write!(f, " over {minutes}m{seconds}s")?,
+            }
+            write!(f, " {}", self.curve.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies a [`Volume`] to a source, counting time in samples so the ramp
+/// is exact whatever the output.
+pub struct Ramp {
+    inner: Box<dyn Source>,
+    volume: Volume,
+    frame: u64,
+}
+
+impl Ramp {
+    pub fn new(inner: Box<dyn Source>, volume: Volume) -> Ramp {
+        Ramp {
+            inner,
+            volume,
+            frame: 0,
+        }
+    }
+}
+
+impl Source for Ramp {
+    fn fill(&mut self, buf: &mut Vec<f32>) -> Result<bool> {
+        let start = buf.len();
+        let more = self.inner.fill(buf)?;
+        for frame in buf[start..].chunks_mut(CHANNELS) {
+            let gain = self.volume.gain(self.frame);
+            for sample in frame {
+                *sample *= gain;
+            }
+            self.frame += 1;
+        }
+        Ok(more)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{Output, OutputConfig, open_output};
+    use std::{env, fs};
+
+    /// Full scale for a given number of frames.
+    struct Constant(u64);
+
+    impl Source for Constant {
+        fn fill(&mut self, buf: &mut Vec<f32>) -> Result<bool> {
+            let frames = self.0.min(u64::from(SAMPLE_RATE / 50));
+            buf.extend((0..frames * CHANNELS as u64).map(|_| 1.0));
+            self.0 -= frames;
+            Ok(self.0 > 0)
+        }
+    }
+
+    /// Renders two seconds of a one second ramp through the file output and
+    /// returns the left channel.
+    fn render(curve: Curve) -> Vec<i16> {
+        let path = env::temp_dir().join(format!(
+            "clockradio-{}-{}.pcm",
+            std::process::id(),
+            curve.as_str()
+        ));
+        let _ = fs::remove_file(&path);
+        let config = OutputConfig {
+            output: Output::File,
+            file: Some(path.clone()),
+            realtime: false,
+            ..OutputConfig::default()
+        };
+        let volume = Volume {
+            target: 100,
+            ramp_secs: 1,
+            curve,
+        };
+        let mut ramp = Ramp::new(Box::new(Constant(2 * u64::from(SAMPLE_RATE))), volume);
+        let mut sink = open_output(&config).unwrap().unwrap();
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            let more = ramp.fill(&mut buf).unwrap();
+            sink.write(&buf).unwrap();
+            if !more {
+                break;
+            }
+        }
+        drop(sink);
+
+        let bytes = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        bytes
+            .chunks(2 * CHANNELS)
+            .map(|frame| i16::from_le_bytes([frame[0], frame[1]]))
+            .collect()
+    }
+
+    #[test]
+    fn renders_each_envelope() {
+        let second = SAMPLE_RATE as usize;
+        let at = |samples: &[i16], seconds: f32| samples[(seconds * second as f32) as usize];
+
+        let linear = render(Curve::Linear);
+        assert_eq!(linear.len(), 2 * second);
+        assert_eq!(linear[0], 0);
+        assert!((at(&linear, 0.25) - 8191).abs() <= 1);
+        assert!((at(&linear, 0.5) - 16383).abs() <= 1);
+        assert_eq!(at(&linear, 1.0), i16::MAX);
+        assert!(linear.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        // -60 dB at the start, -30 dB half way.
+        let exponential = render(Curve::Exponential);
+        assert_eq!(exponential[0], 0);
+        assert!((at(&exponential, 0.5) - 1036).abs() <= 2);
+        assert!((at(&exponential, 0.9) - 16423).abs() <= 20);
+        assert_eq!(at(&exponential, 1.5), i16::MAX);
+
+        let stepped = render(Curve::Stepped);
+        let levels: Vec<i16> = [0.1, 0.3, 0.5, 0.7, 0.9, 1.5]
+            .into_iter()
+            .map(|seconds| at(&stepped, seconds))
+            .collect();
+        assert_eq!(levels, [6553, 13106, 19660, 26213, i16::MAX, i16::MAX]);
+        assert_eq!(at(&stepped, 0.19), at(&stepped, 0.0));
+    }
+
+    #[test]
+    fn parses_the_editor_syntax() {
+        assert_eq!(Volume::parse(""), Ok(Volume::default()));
+        assert_eq!(
+            Volume::parse("80% over 2m exp"),
+            Ok(Volume {
+                target: 80,
+                ramp_secs: 120,
+                curve: Curve::Exponential,
+            })
+        );
+        assert_eq!(
+            Volume::parse("50 1m30s Steps"),
+            Ok(Volume {
+                target: 50,
+                ramp_secs: 90,
+                curve: Curve::Stepped,
+            })
+        );
+        assert_eq!(
+            Volume::parse("0%"),
+            Err("Volume must be 1% to 100%".to_string())
+        );
+        assert_eq!(
+            Volume::parse("101%"),
+            Err("Volume must be 1% to 100%".to_string())
+        );
+        assert_eq!(
+            Volume::parse("80% loud"),
+            Err("Unknown volume setting \"loud\"".to_string())
+        );
+        assert_eq!(Volume::parse("60m").unwrap().ramp_secs, 3600);
+        for too_long in ["61m", "3601s", "71582789m", "1m4294967295s"] {
+            assert_eq!(
+                Volume::parse(too_long),
+                Err(format!("Unknown volume setting \"{too_long}\""))
+            );
+        }
+    }
+
+    #[test]
+    fn displays_what_it_parses() {
+        for text in [
+            "100%",
+            "80% over 30s linear",
+            "60% over 2m exp",
+            "25% over 1m30s stepped",
+        ] {
+            assert_eq!(Volume::parse(text).unwrap().to_string(), text);
+        }
+    }
+}
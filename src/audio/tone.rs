@@ -0,0 +1,46 @@
+use super::{SAMPLE_RATE, Source};
+use anyhow::Result;
+use std::f32::consts::TAU;
+
+const PITCH: f32 = 880.0;
+const VOLUME: f32 = 0.5;
+/// Four short beeps followed by a pause, in seconds.
+const BEEP: f32 = 0.1;
+const GAP: f32 = 0.1;
+const PAUSE: f32 = 0.6;
+const BEEPS: u32 = 4;
+/// Fade at the edges of each beep to avoid clicks.
+const RAMP: f32 = 0.005;
+
+/// The built-in alarm sound: an endless beep-beep-beep-beep pattern.
+#[derive(Default)]
+pub struct Tone {
+    frame: u64,
+}
+
+impl Tone {
+    fn sample(&self) -> f32 {
+        let period = BEEPS as f32 * (BEEP + GAP) + PAUSE;
+        let period_frames = (period * SAMPLE_RATE as f32) as u64;
+        // Time within the current pattern, kept small for f32 precision.
+        let t = (self.frame % period_frames) as f32 / SAMPLE_RATE as f32;
+        let in_beep = t % (BEEP + GAP);
+        if t >= BEEPS as f32 * (BEEP + GAP) || in_beep >= BEEP {
+            return 0.0;
+        }
+        let envelope = (in_beep / RAMP).min((BEEP - in_beep) / RAMP).min(1.0);
+        (t * PITCH * TAU).sin() * envelope * VOLUME
+    }
+}
+
+impl Source for Tone {
+    fn fill(&mut self, buf: &mut Vec<f32>) -> Result<bool> {
+        // About 20 ms per call keeps stopping responsive.
+        for _ in 0..SAMPLE_RATE / 50 {
+            let sample = self.sample();
+            buf.extend([sample, sample]);
+            self.frame += 1;
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,226 @@
+//! Fitting theme colors to what the terminal can show.
+
+use crate::theme::Theme;
+use clap::ValueEnum;
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+use std::env;
+
+/// How many colors the terminal has.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum ColorMode {
+    #[value(name = "truecolor")]
+    TrueColor,
+    #[value(name = "256")]
+    Ansi256,
+    #[value(name = "16")]
+    Ansi16,
+    /// No colors, only bold, dim and reverse text.
+    #[value(name = "none")]
+    Mono,
+}
+
+impl ColorMode {
+    /// Guesses from the environment: `NO_COLOR`, then `COLORTERM`, then
+    /// `TERM`.
+    pub fn detect() -> ColorMode {
+        let var = |name| env::var(name).unwrap_or_default();
+        ColorMode::from_env(&var("NO_COLOR"), &var("COLORTERM"), &var("TERM"))
+    }
+
+    fn from_env(no_color: &str, colorterm: &str, term: &str) -> ColorMode {
+        if !no_color.is_empty() || term == "dumb" {
+            ColorMode::Mono
+        } else if matches!(colorterm, "truecolor" | "24bit")
+            || term.ends_with("-direct")
+            || term.contains("truecolor")
+        {
+            ColorMode::TrueColor
+        } else if term.contains("256color") {
+            ColorMode::Ansi256
+        } else {
+            ColorMode::Ansi16
+        }
+    }
+
+    /// The closest color the terminal can show. Named colors are left
+    /// alone, every terminal has them.
+    pub fn fit(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorMode::Ansi256, Color::Rgb(r, g, b)) => {
+                // The 6x6x6 cube and the grays; the first 16 vary between
+                // terminals.
+                Color::Indexed(nearest(16..=255, (r, g, b)))
+            }
+            (ColorMode::Ansi16, Color::Rgb(r, g, b)) => ansi16((r, g, b)),
+            (ColorMode::Ansi16, Color::Indexed(index)) => match index {
+                0..=15 => ANSI[index as usize],
+                _ => ansi16(indexed_rgb(index)),
+            },
+            _ => color,
+        }
+    }
+}
+
+/// The 16 colors in the order of their indices.
+const ANSI: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// The 16-color entry with the hue of `rgb`, bright if `rgb` is. Nearest by
+/// distance would turn most pastels gray.
+fn ansi16((r, g, b): (u8, u8, u8)) -> Color {
+    let max = r.max(g).max(b);
+    let chroma = max - r.min(g).min(b);
+    if chroma < max / 4 || max < 64 {
+        return match max {
+            0..=63 => Color::Black,
+            64..=159 => Color::DarkGray,
+            160..=223 => Color::Gray,
+            _ => Color::White,
+        };
+    }
+    let (red, green, blue, c) = (f32::from(r), f32::from(g), f32::from(b), f32::from(chroma));
+    let hue = if max == r {
+        60.0 * ((green - blue) / c).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((blue - red) / c + 2.0)
+    } else {
+        60.0 * ((red - green) / c + 4.0)
+    };
+    // Red, yellow, green, cyan, blue and magenta, each 60 degrees wide.
+    let sextant = ((hue + 30.0) / 60.0) as usize % 6;
+    let index = [1, 3, 2, 6, 4, 5][sextant] + if max >= 192 { 8 } else { 0 };
+    ANSI[index]
+}
+
+/// What the 256-color palette index `index`, past the first 16, looks like
+/// in xterm.
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match index {
+        ..=231 => {
+            let cube = index.saturating_sub(16);
+            (
+                LEVELS[(cube / 36) as usize],
+                LEVELS[(cube / 6 % 6) as usize],
+                LEVELS[(cube % 6) as usize],
+            )
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// The palette index in `candidates` closest to `rgb`.
+fn nearest(candidates: std::ops::RangeInclusive<u8>, rgb: (u8, u8, u8)) -> u8 {
+    candidates
+        .min_by_key(|&index| distance(indexed_rgb(index), rgb))
+        .unwrap_or(0)
+}
+
+/// How different two colors look, weighted for the eye ("redmean"), so pink
+/// stays pinkish instead of turning gray.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> i32 {
+    let mean_red = (i32::from(a.0) + i32::from(b.0)) / 2;
+    let (dr, dg, db) = (
+        i32::from(a.0) - i32::from(b.0),
+        i32::from(a.1) - i32::from(b.1),
+        i32::from(a.2) - i32::from(b.2),
+    );
+    (((512 + mean_red) * dr * dr) >> 8) + 4 * dg * dg + (((767 - mean_red) * db * db) >> 8)
+}
+
+/// Turns the theme's colors in `buf` into text attributes for terminals
+/// without color: highlights become reverse text, the accent bold and the
+/// scenery dim.
+pub fn monochrome(buf: &mut Buffer, theme: &Theme) {
+    for cell in &mut buf.content {
+        let modifier = if cell.bg == theme.accent && theme.accent != theme.background {
+            Modifier::REVERSED
+        } else if cell.fg == theme.alert {
+            Modifier::BOLD | Modifier::REVERSED
+        } else if cell.fg == theme.accent {
+            Modifier::BOLD
+        } else if cell.fg == theme.dim {
+            Modifier::DIM
+        } else {
+            Modifier::empty()
+        };
+        cell.modifier |= modifier;
+        cell.fg = Color::Reset;
+        cell.bg = Color::Reset;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_from_the_environment() {
+        assert_eq!(
+            ColorMode::from_env("", "truecolor", "xterm-256color"),
+            ColorMode::TrueColor
+        );
+        assert_eq!(
+            ColorMode::from_env("", "", "xterm-256color"),
+            ColorMode::Ansi256
+        );
+        assert_eq!(ColorMode::from_env("", "", "xterm"), ColorMode::Ansi16);
+        assert_eq!(ColorMode::from_env("", "", "dumb"), ColorMode::Mono);
+        assert_eq!(
+            ColorMode::from_env("1", "truecolor", "xterm"),
+            ColorMode::Mono
+        );
+    }
+
+    #[test]
+    fn fits_the_pink_theme() {
+        let pink = Theme::default();
+        assert_eq!(ColorMode::Ansi256.fit(pink.accent), Color::Indexed(204));
+        assert_eq!(ColorMode::Ansi256.fit(pink.dim), Color::Indexed(241));
+        assert_eq!(ColorMode::Ansi16.fit(pink.accent), Color::LightRed);
+        assert_eq!(ColorMode::Ansi16.fit(Color::Rgb(110, 72, 0)), Color::Yellow);
+        assert_eq!(ColorMode::Ansi16.fit(pink.dim), Color::DarkGray);
+        assert_eq!(ColorMode::Ansi16.fit(Color::Indexed(196)), Color::LightRed);
+        assert_eq!(ColorMode::Ansi16.fit(Color::White), Color::White);
+        assert_eq!(
+            ColorMode::TrueColor.fit(pink.accent),
+            Color::Rgb(255, 107, 138)
+        );
+    }
+
+    #[test]
+    fn marks_roles_with_modifiers() {
+        let pink = Theme::default();
+        let mut buf = Buffer::with_lines(["ab"]);
+        buf.get_mut(0, 0).set_fg(pink.accent);
+        buf.get_mut(1, 0)
+            .set_fg(pink.background)
+            .set_bg(pink.accent);
+        monochrome(&mut buf, &pink);
+        assert_eq!(buf.get(0, 0).modifier, Modifier::BOLD);
+        assert_eq!(buf.get(1, 0).modifier, Modifier::REVERSED);
+        assert_eq!(buf.get(1, 0).bg, Color::Reset);
+    }
+}
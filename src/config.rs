@@ -0,0 +1,226 @@
+use crate::{audio::OutputConfig, face::Language, paths, theme::Theme, weather::WeatherConfig};
+use anyhow::{Context, Result, bail};
+use chrono::format::{Item, StrftimeItems};
+use serde::Deserialize;
+use std::{fs, io::ErrorKind, path::Path, time::Duration};
+
+/// Hand-written settings from `config.toml`. Every key is optional.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The `[colors]` section, a theme of its own named `custom`.
+    pub colors: Option<Theme>,
+    pub clock: ClockConfig,
+    pub animation: AnimationConfig,
+    pub audio: OutputConfig,
+    pub radio: RadioConfig,
+    pub weather: WeatherConfig,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClockConfig {
+    pub twelve_hour: bool,
+    pub show_seconds: bool,
+    /// Flash the colons once a second.
+    pub blink_colon: bool,
+    /// How 12-hour mode marks the time of day.
+    pub am_pm: AmPm,
+    /// strftime format for the big clock, overrides `twelve_hour` and `show_seconds`.
+    pub time_format: Option<String>,
+    pub date_format: String,
+    /// Font of the big clock: `block`, `seven-segment`, `thin`, `braille`,
+    /// `half-block` or the path of a FIGlet `.flf` file.
+    pub font: String,
+    /// Language of the word clock face: `en`, `de` or `fr`.
+    pub language: Language,
+}
+
+impl Default for ClockConfig {
+    fn default() -> ClockConfig {
+        ClockConfig {
+            twelve_hour: false,
+            show_seconds: false,
+            blink_colon: false,
+            am_pm: AmPm::Big,
+            time_format: None,
+            date_format: "%A, %B %d, %Y".to_string(),
+            font: "block".to_string(),
+            language: Language::En,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AmPm {
+    /// Big letters after the digits, or `compact` where they don't fit.
+    Big,
+    /// A small AM/PM beside the digits.
+    Compact,
+    Hidden,
+}
+
+impl ClockConfig {
+    /// Format of the big clock.
+    pub fn time_format(&self) -> String {
+        if let Some(format) = &self.time_format {
+            return format.clone();
+        }
+        let mut format = self.hour_minute_format().to_string();
+        if self.show_seconds {
+            format.push_str(":%S");
+        }
+        format
+    }
+
+    /// The big clock without its seconds.
+    pub fn hour_minute_format(&self) -> &'static str {
+        if self.twelve_hour { "%I:%M" } else { "%H:%M" }
+    }
+
+    /// Whether the big clock ends in seconds, which may be drawn smaller.
+    pub fn seconds(&self) -> bool {
+        self.show_seconds && self.time_format.is_none()
+    }
+
+    /// How the big clock shows AM/PM, `None` when it doesn't.
+    pub fn am_pm(&self) -> Option<AmPm> {
+        let shown = self.twelve_hour && self.time_format.is_none() && self.am_pm != AmPm::Hidden;
+        shown.then_some(self.am_pm)
+    }
+
+    /// Format for times typed into the alarm editor.
+    pub fn input_time_format(&self) -> &'static str {
+        if self.twelve_hour {
+            "%-I:%M%P"
+        } else {
+            "%H:%M"
+        }
+    }
+
+    /// Format for alarm times in lists and the status bar.
+    pub fn short_time_format(&self) -> &'static str {
+        if self.twelve_hour {
+            "%I:%M %p"
+        } else {
+            "%H:%M"
+        }
+    }
+
+    /// chrono panics while drawing a bad format, so they are checked up front.
+    fn check_formats(&self) -> Result<()> {
+        let formats = [
+            ("time_format", self.time_format.as_deref()),
+            ("date_format", Some(self.date_format.as_str())),
+        ];
+        for (key, format) in formats {
+            if let Some(format) = format
+                && StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+            {
+                bail!("clock.{key} \"{format}\" is not a valid strftime format");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnimationConfig {
+    pub enabled: bool,
+    /// Redraws per second.
+    pub fps: u32,
+}
+
+impl Default for AnimationConfig {
+    fn default() -> AnimationConfig {
+        AnimationConfig {
+            enabled: true,
+            fps: 7,
+        }
+    }
+}
+
+impl AnimationConfig {
+    pub fn frame_interval(&self) -> Duration {
+        Duration::from_millis(1000 / u64::from(self.fps.clamp(1, 60)))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RadioConfig {
+    /// Stream played by 'r', an http:// URL.
+    pub station: Option<String>,
+    /// Seconds an alarm waits for its station before ringing the tone instead.
+    pub alarm_timeout: u64,
+}
+
+impl Default for RadioConfig {
+    fn default() -> RadioConfig {
+        RadioConfig {
+            station: None,
+            alarm_timeout: 15,
+        }
+    }
+}
+
+impl RadioConfig {
+    pub fn alarm_timeout(&self) -> Duration {
+        Duration::from_secs(self.alarm_timeout)
+    }
+}
+
+impl Config {
+    /// Loads `path`, or `config.toml` in the XDG config directory when no
+    /// path is given. Only the default file may be missing.
+    pub fn load(path: Option<&Path>) -> Result<Config> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match paths::config_dir() {
+                Some(dir) => (dir.join("config.toml"), false),
+                None => return Ok(Config::default()),
+            },
+        };
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound && !required => {
+                return Ok(Config::default());
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("cannot read {}", path.display()));
+            }
+        };
+        Config::parse(&text).with_context(|| format!("invalid config file {}", path.display()))
+    }
+
+    fn parse(text: &str) -> Result<Config> {
+        let config: Config = toml::from_str(text)?;
+        config.clock.check_formats()?;
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_bad_clock_formats() {
+        let err = Config::parse("[clock]\ndate_format = \"%Q\"")
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "clock.date_format \"%Q\" is not a valid strftime format"
+        );
+        let err = Config::parse("[clock]\ntime_format = \"%H:%\"")
+            .err()
+            .unwrap();
+        assert!(err.to_string().starts_with("clock.time_format"));
+        assert!(Config::parse("[clock]\ntime_format = \"%-I.%M %p\"").is_ok());
+        assert!(Config::parse("").is_ok());
+    }
+}
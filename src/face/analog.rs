@@ -0,0 +1,150 @@
+//! A dial drawn in braille, with a marker at the next alarm.
+
+use super::{ClockFace, date_line};
+use crate::{config::ClockConfig, theme::Theme};
+use chrono::{DateTime, Local, Timelike};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    symbols,
+    widgets::{
+        Widget,
+        canvas::{self, Canvas},
+    },
+};
+
+pub struct Analog<'a> {
+    pub clock: &'a ClockConfig,
+    pub colors: &'a Theme,
+    /// When the next alarm rings.
+    pub alarm: Option<DateTime<Local>>,
+}
+
+impl ClockFace for Analog<'_> {
+    fn render(&self, now: DateTime<Local>, area: Rect, buf: &mut Buffer) {
+        let colors = self.colors;
+        let dial = date_line(now, self.clock, colors, area, buf);
+        if dial.width < 2 || dial.height < 2 {
+            return;
+        }
+
+        // Braille dots are about square, two across and four down a cell, so
+        // the bounds follow the shape of the area to keep the dial round.
+        let aspect = f64::from(dial.width) / (f64::from(dial.height) * 2.0);
+        let (x_bound, y_bound) = if aspect > 1.0 {
+            (1.15 * aspect, 1.15)
+        } else {
+            (1.15, 1.15 / aspect)
+        };
+        let dot = x_bound / f64::from(dial.width);
+        // `turns` clockwise from twelve o'clock.
+        let point = |turns: f64, radius: f64| {
+            let angle = turns * std::f64::consts::TAU;
+            (radius * angle.sin(), radius * angle.cos())
+        };
+        let line = |from: (f64, f64), to: (f64, f64), color| canvas::Line {
+            x1: from.0,
+            y1: from.1,
+            x2: to.0,
+            y2: to.1,
+            color,
+        };
+
+        let second = f64::from(now.second());
+        let minute = f64::from(now.minute()) + second / 60.0;
+        let hour = f64::from(now.hour() % 12) + minute / 60.0;
+        let alarm = self
+            .alarm
+            .map(|at| (f64::from(at.hour() % 12) + f64::from(at.minute()) / 60.0) / 12.0);
+        let big = dial.height >= 12;
+        let seconds = self.clock.show_seconds;
+
+        Canvas::default()
+            .marker(symbols::Marker::Braille)
+            .x_bounds([-x_bound, x_bound])
+            .y_bounds([-y_bound, y_bound])
+            .paint(|ctx| {
+                ctx.draw(&canvas::Circle {
+                    x: 0.0,
+                    y: 0.0,
+                    radius: 1.0,
+                    color: colors.accent,
+                });
+                for tick in 0..60 {
+                    let turns = f64::from(tick) / 60.0;
+                    if tick % 5 == 0 {
+                        let inner = if tick % 15 == 0 { 0.8 } else { 0.87 };
+                        ctx.draw(&line(
+                            point(turns, inner),
+                            point(turns, 0.95),
+                            colors.accent,
+                        ));
+                    } else if big {
+                        let (x, y) = point(turns, 0.94);
+                        ctx.draw(&canvas::Points {
+                            coords: &[(x, y)],
+                            color: colors.dim,
+                        });
+                    }
+                }
+                if let Some(turns) = alarm {
+                    // A wedge outside the rim, pointing in.
+                    let tip = point(turns, 1.02);
+                    for side in [-0.012, 0.0, 0.012] {
+                        ctx.draw(&line(tip, point(turns + side, 1.13), colors.text));
+                    }
+                }
+                ctx.layer();
+
+                ctx.draw(&line((0.0, 0.0), point(minute / 60.0, 0.78), colors.text));
+                // The hour hand is drawn three dots wide.
+                let (x, y) = point(hour / 12.0, 0.5);
+                for offset in [-dot, 0.0, dot] {
+                    let (dx, dy) = (offset * y / 0.5, -offset * x / 0.5);
+                    ctx.draw(&line((dx, dy), (x + dx, y + dy), colors.text));
+                }
+                if seconds {
+                    ctx.layer();
+                    ctx.draw(&line(
+                        point(second / 60.0, -0.15),
+                        point(second / 60.0, 0.88),
+                        colors.alert,
+                    ));
+                }
+            })
+            .render(dial, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::face::tests::{draw, evening, plain};
+    use chrono::TimeDelta;
+
+    #[test]
+    fn draws_hands_and_the_alarm_marker() {
+        let clock = ClockConfig::default();
+        let colors = plain();
+        let face = Analog {
+            clock: &clock,
+            colors: &colors,
+            alarm: Some(evening() + TimeDelta::hours(11)),
+        };
+        // The alarm, at 6:25, is the wedge under the rim.
+        draw(&face, evening(), 30, 12).assert_buffer_lines([
+            "           ⢀⣀⡤⢤⠤⣄⣀            ",
+            "        ⣠⠔⢏⠁  ⠸   ⢉⠗⢤⡀        ",
+            "      ⢀⠞⠁            ⠙⢆       ",
+            "     ⢠⠏⠑             ⠐⠉⢧      ",
+            "     ⡜                 ⠘⡄     ",
+            "     ⡗⠒⠂    ⢀⢔⢕⡄      ⠒⠒⡇     ",
+            "     ⢱     ⡠⣪⠗⠁⠘⡄      ⢰⠁     ",
+            "     ⠈⢧⠔   ⠘⠁   ⠘⡄   ⠐⢤⠏      ",
+            "       ⠳⣄ ⢀      ⠘⠄ ⢀⡴⠃       ",
+            "        ⠈⠑⠧⢄⣀⡀⢸ ⣀⣀⠤⠗⠉         ",
+            "           ⠐⠞⠍⠉⠉⠁             ",
+            "  Thursday, October 15, 2026  ",
+        ]);
+    }
+}
@@ -0,0 +1,118 @@
+//! A binary-coded decimal clock: every digit of the time is a column of
+//! bits, 8 at the top and 1 at the bottom.
+
+use super::{ClockFace, centered, date_line};
+use crate::{config::ClockConfig, theme::Theme};
+use chrono::{DateTime, Local, Timelike};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+};
+
+pub struct Binary<'a> {
+    pub clock: &'a ClockConfig,
+    pub colors: &'a Theme,
+}
+
+/// Bits each digit needs: tens of hours go up to 2 and tens of minutes and
+/// seconds to 5.
+const BITS: [u32; 6] = [2, 4, 3, 4, 3, 4];
+
+impl ClockFace for Binary<'_> {
+    fn render(&self, now: DateTime<Local>, area: Rect, buf: &mut Buffer) {
+        let area = date_line(now, self.clock, self.colors, area, buf);
+        let hour = if self.clock.twelve_hour {
+            (now.hour() + 11) % 12 + 1
+        } else {
+            now.hour()
+        };
+        let mut digits = vec![hour / 10, hour % 10, now.minute() / 10, now.minute() % 10];
+        if self.clock.show_seconds {
+            digits.extend([now.second() / 10, now.second() % 10]);
+        }
+
+        let lit = Style::default().fg(self.colors.accent);
+        let unlit = Style::default().fg(self.colors.dim);
+        let column_gap = |column: usize| match column {
+            0 => "",
+            // Hours, minutes and seconds stand apart.
+            column if column % 2 == 0 => "   ",
+            _ => " ",
+        };
+        let mut lines: Vec<Line> = (0..4)
+            .rev()
+            .map(|bit| {
+                let mut spans = Vec::new();
+                for (column, digit) in digits.iter().enumerate() {
+                    spans.push(Span::raw(column_gap(column)));
+                    spans.push(if bit >= BITS[column] {
+                        Span::raw(" ")
+                    } else if digit & (1 << bit) != 0 {
+                        Span::styled("●", lit)
+                    } else {
+                        Span::styled("○", unlit)
+                    });
+                }
+                Line::from(spans)
+            })
+            .collect();
+        let labels: String = digits
+            .iter()
+            .enumerate()
+            .map(|(column, digit)| format!("{}{digit}", column_gap(column)))
+            .collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(labels, unlit)));
+        centered(lines, area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::face::tests::{draw, evening, plain};
+
+    #[test]
+    fn draws_a_column_per_digit() {
+        let clock = ClockConfig {
+            show_seconds: true,
+            ..ClockConfig::default()
+        };
+        let colors = plain();
+        let face = Binary {
+            clock: &clock,
+            colors: &colors,
+        };
+        // 19:25:09
+        draw(&face, evening(), 30, 8).assert_buffer_lines([
+            "          ●     ○     ●       ",
+            "          ○   ○ ●   ○ ○       ",
+            "        ○ ○   ● ○   ○ ○       ",
+            "        ● ●   ○ ●   ○ ●       ",
+            "                              ",
+            "        1 9   2 5   0 9       ",
+            "                              ",
+            "  Thursday, October 15, 2026  ",
+        ]);
+    }
+
+    #[test]
+    fn counts_hours_to_twelve() {
+        let clock = ClockConfig {
+            twelve_hour: true,
+            ..ClockConfig::default()
+        };
+        let colors = plain();
+        let face = Binary {
+            clock: &clock,
+            colors: &colors,
+        };
+        let backend = draw(&face, evening(), 20, 7);
+        let labels: String = (0..20)
+            .map(|x| backend.buffer().get(x, 5).symbol().to_string())
+            .collect();
+        assert_eq!(labels.trim(), "0 7   2 5");
+    }
+}
@@ -0,0 +1,196 @@
+//! The big digits.
+
+use super::ClockFace;
+use crate::{
+    config::{AmPm, ClockConfig},
+    font::{self, Font},
+    theme::Theme,
+};
+use chrono::{DateTime, Local};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+
+pub struct Digital<'a> {
+    pub clock: &'a ClockConfig,
+    pub font: &'a Font,
+    /// Smaller digits for seconds when the clock is too wide.
+    pub seconds_font: Option<&'a Font>,
+    pub colors: &'a Theme,
+}
+
+impl ClockFace for Digital<'_> {
+    fn render(&self, now: DateTime<Local>, area: Rect, buf: &mut Buffer) {
+        // Leave a line for the date.
+        let digits = big_clock(
+            now,
+            self.clock,
+            self.font,
+            self.seconds_font,
+            area.width,
+            area.height.saturating_sub(1),
+        );
+        // A blank line before the date, where it fits and the clock is big.
+        let gap = usize::from(digits.len() > 1 && digits.len() + 2 <= area.height as usize);
+        let top_padding = (area.height as usize).saturating_sub(digits.len() + gap + 1) / 2;
+
+        let mut lines = vec![Line::from(""); top_padding];
+        let style = Style::default()
+            .fg(self.colors.accent)
+            .add_modifier(Modifier::BOLD);
+        lines.extend(
+            digits
+                .into_iter()
+                .map(|line| Line::from(Span::styled(line, style))),
+        );
+        if gap > 0 {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            now.format(&self.clock.date_format).to_string(),
+            Style::default().fg(self.colors.text),
+        )));
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .render(area, buf);
+    }
+}
+
+/// Puts `block` one column to the right of `lines`, its top level with line
+/// `row`.
+fn append_block(lines: &mut [String], block: &[String], row: usize) {
+    let width = block
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (i, line) in lines.iter_mut().enumerate() {
+        let part = i
+            .checked_sub(row)
+            .and_then(|i| block.get(i))
+            .map_or("", String::as_str);
+        line.push(' ');
+        line.push_str(part);
+        line.push_str(&" ".repeat(width - part.chars().count()));
+    }
+}
+
+/// The big clock for `now` in the largest size that fits `width` by
+/// `height`. Before a size is given up, 12-hour mode's AM/PM letters become a
+/// small tag and then the seconds turn small. Where no size fits, the time is
+/// a single line of text.
+pub fn big_clock(
+    now: DateTime<Local>,
+    clock: &ClockConfig,
+    font: &Font,
+    small: Option<&Font>,
+    width: u16,
+    height: u16,
+) -> Vec<String> {
+    let colon = !clock.blink_colon || now.timestamp_subsec_millis() < 500;
+    let fits = |lines: &[String]| {
+        lines.len() <= height as usize
+            && lines
+                .iter()
+                .all(|line| line.chars().count() <= width as usize)
+    };
+    let time = now.format(&clock.time_format()).to_string();
+    let meridiem = now.format("%p").to_string();
+    let am_pm = clock.am_pm();
+    let largest = if font.scalable() {
+        height as usize / font.height()
+    } else {
+        1
+    };
+    for scale in (1..=largest.max(1)).rev() {
+        let big = |text: &str| font::scale(font.render(text, colon), scale);
+        if am_pm == Some(AmPm::Big) && font.covers(&meridiem) {
+            let lines = big(&format!("{time} {meridiem}"));
+            if fits(&lines) {
+                return lines;
+            }
+        }
+        let mut lines = big(&time);
+        if am_pm.is_some() {
+            append_block(&mut lines, std::slice::from_ref(&meridiem), 0);
+        }
+        if fits(&lines) {
+            return lines;
+        }
+        if let Some(small) = small.filter(|_| clock.seconds()) {
+            // Small seconds sit low, leaving room for the tag above them.
+            let mut side = vec![String::new(); (font.height() - small.height()) * scale];
+            if am_pm.is_some() {
+                side[0] = meridiem.clone();
+            }
+            side.extend(font::scale(
+                small.render(&now.format("%S").to_string(), true),
+                scale,
+            ));
+            let mut lines = big(&now.format(clock.hour_minute_format()).to_string());
+            append_block(&mut lines, &side, 0);
+            if fits(&lines) {
+                return lines;
+            }
+        }
+    }
+    let mut line = if colon { time } else { time.replace(':', " ") };
+    if am_pm.is_some() {
+        line = format!("{line} {meridiem}");
+    }
+    vec![line]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::face::tests::{draw, evening, plain};
+    use ratatui::layout::Rect;
+
+    #[test]
+    fn draws_digits_above_the_date() {
+        let clock = ClockConfig::default();
+        let font = Font::load("block").unwrap();
+        let colors = plain();
+        let face = Digital {
+            clock: &clock,
+            font: &font,
+            seconds_font: None,
+            colors: &colors,
+        };
+        let mut expected = Buffer::with_lines([
+            "    ██   ███████         ███████ ███████",
+            "   ███   ██   ██    ██        ██ ██     ",
+            "    ██   ██   ██    ██        ██ ██     ",
+            "    ██   ███████         ███████ ███████",
+            "    ██        ██    ██   ██           ██",
+            "    ██        ██    ██   ██           ██",
+            " ███████ ███████         ███████ ███████",
+            "                                        ",
+            "       Thursday, October 15, 2026       ",
+        ]);
+        expected.set_style(Rect::new(1, 0, 39, 7), Modifier::BOLD);
+        draw(&face, evening(), 40, 9).assert_buffer(&expected);
+    }
+
+    #[test]
+    fn falls_back_to_one_line() {
+        let clock = ClockConfig::default();
+        let font = Font::load("block").unwrap();
+        let lines = big_clock(evening(), &clock, &font, None, 30, 7);
+        assert_eq!(lines, ["19:25"]);
+    }
+
+    #[test]
+    fn scales_up_to_fill_the_space() {
+        let clock = ClockConfig::default();
+        let font = Font::load("block").unwrap();
+        let lines = big_clock(evening(), &clock, &font, None, 100, 20);
+        assert_eq!(lines.len(), 14);
+        assert_eq!(lines[0].chars().count(), 78);
+    }
+}
@@ -0,0 +1,86 @@
+//! Seconds since the Unix epoch with the ISO 8601 time, local and UTC.
+
+use super::{ClockFace, centered};
+use crate::{font::Font, theme::Theme};
+use chrono::{DateTime, Local, Utc};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+pub struct Epoch<'a> {
+    pub font: &'a Font,
+    pub colors: &'a Theme,
+}
+
+impl ClockFace for Epoch<'_> {
+    fn render(&self, now: DateTime<Local>, area: Rect, buf: &mut Buffer) {
+        let accent = Style::default()
+            .fg(self.colors.accent)
+            .add_modifier(Modifier::BOLD);
+        let text = Style::default().fg(self.colors.text);
+        let dim = Style::default().fg(self.colors.dim);
+
+        let timestamp = now.timestamp().to_string();
+        let big = self.font.render(&timestamp, true);
+        let fits =
+            big.len() + 4 <= area.height as usize && big[0].chars().count() <= area.width as usize;
+        let mut lines: Vec<Line> = if fits {
+            big.into_iter()
+                .map(|line| Line::from(Span::styled(line, accent)))
+                .collect()
+        } else {
+            vec![Line::from(Span::styled(timestamp, accent))]
+        };
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            now.format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+            text,
+        )));
+        lines.push(Line::from(Span::styled(
+            now.with_timezone(&Utc)
+                .format("%Y-%m-%dT%H:%M:%SZ")
+                .to_string(),
+            text,
+        )));
+        // The ISO week date.
+        lines.push(Line::from(Span::styled(
+            now.format("%G-W%V-%u, day %j").to_string(),
+            dim,
+        )));
+        centered(lines, area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::face::tests::{draw, plain};
+    use chrono::TimeZone;
+
+    #[test]
+    fn shows_the_timestamp_and_iso_times() {
+        let font = Font::load("block").unwrap();
+        let colors = plain();
+        let face = Epoch {
+            font: &font,
+            colors: &colors,
+        };
+        // Noon UTC, so the date is the same in every time zone.
+        let now = Local.timestamp_opt(1_792_065_600, 0).unwrap();
+        let local = now.format("%Y-%m-%dT%H:%M:%S%:z");
+        let mut expected = Buffer::with_lines([
+            "                              ".to_string(),
+            "          1792065600          ".to_string(),
+            "                              ".to_string(),
+            format!("   {local}  "),
+            "     2026-10-15T12:00:00Z     ".to_string(),
+            "      2026-W42-4, day 288     ".to_string(),
+            "                              ".to_string(),
+        ]);
+        expected.set_style(Rect::new(10, 1, 10, 1), Modifier::BOLD);
+        draw(&face, now, 30, 7).assert_buffer(&expected);
+    }
+}
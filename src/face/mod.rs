@@ -0,0 +1,173 @@
+//! Interchangeable ways of showing the time inside the clock box, switched
+//! with 'c'.
+
+mod analog;
+mod binary;
+mod digital;
+mod epoch;
+mod words;
+
+pub use analog::Analog;
+pub use binary::Binary;
+pub use digital::{Digital, big_clock};
+pub use epoch::Epoch;
+pub use words::{Language, Words};
+
+use crate::{config::ClockConfig, theme::Theme};
+use chrono::{DateTime, Local};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+use serde::{Deserialize, Serialize};
+
+pub trait ClockFace {
+    /// Draws the time `now` into `area`, which is inside the clock's border.
+    fn render(&self, now: DateTime<Local>, area: Rect, buf: &mut Buffer);
+}
+
+/// Which face the clock shows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Face {
+    #[default]
+    Digital,
+    Analog,
+    Binary,
+    Words,
+    Epoch,
+}
+
+impl Face {
+    const ALL: [Face; 5] = [
+        Face::Digital,
+        Face::Analog,
+        Face::Binary,
+        Face::Words,
+        Face::Epoch,
+    ];
+
+    /// The face 'c' switches to.
+    pub fn next(self) -> Face {
+        let index = Face::ALL.iter().position(|face| *face == self).unwrap_or(0);
+        Face::ALL[(index + 1) % Face::ALL.len()]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Face::Digital => "digital",
+            Face::Analog => "analog",
+            Face::Binary => "binary",
+            Face::Words => "words",
+            Face::Epoch => "epoch",
+        }
+    }
+}
+
+/// Draws the date on the bottom line of `area`, if there are two or more,
+/// and returns the rest.
+fn date_line(
+    now: DateTime<Local>,
+    clock: &ClockConfig,
+    colors: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) -> Rect {
+    if area.height < 2 {
+        return area;
+    }
+    let date = Rect {
+        y: area.bottom() - 1,
+        height: 1,
+        ..area
+    };
+    Paragraph::new(Span::styled(
+        now.format(&clock.date_format).to_string(),
+        Style::default().fg(colors.text),
+    ))
+    .alignment(Alignment::Center)
+    .render(date, buf);
+    Rect {
+        height: area.height - 1,
+        ..area
+    }
+}
+
+/// Draws `lines` centered both ways in `area`.
+fn centered(lines: Vec<Line>, area: Rect, buf: &mut Buffer) {
+    let top = (area.height as usize).saturating_sub(lines.len()) / 2;
+    let mut padded = vec![Line::from(""); top];
+    padded.extend(lines);
+    Paragraph::new(padded)
+        .alignment(Alignment::Center)
+        .render(area, buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use ratatui::{Terminal, backend::TestBackend, style::Color};
+
+    /// Thursday 15 October 2026, 19:25:09.
+    pub fn evening() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 15, 19, 25, 9).unwrap()
+    }
+
+    /// Theme that leave the buffer's styles alone, so expected buffers
+    /// only need modifiers.
+    pub fn plain() -> Theme {
+        Theme {
+            name: "plain".to_string(),
+            accent: Color::Reset,
+            text: Color::Reset,
+            background: Color::Reset,
+            dim: Color::Reset,
+            alert: Color::Reset,
+        }
+    }
+
+    pub fn draw(
+        face: &dyn ClockFace,
+        now: DateTime<Local>,
+        width: u16,
+        height: u16,
+    ) -> TestBackend {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|f| face.render(now, f.size(), f.buffer_mut()))
+            .unwrap();
+        terminal.backend().clone()
+    }
+
+    #[test]
+    fn cycles_through_every_face() {
+        let mut face = Face::Digital;
+        let mut seen = Vec::new();
+        for _ in 0..Face::ALL.len() {
+            seen.push(face);
+            face = face.next();
+        }
+        assert_eq!(face, Face::Digital);
+        assert_eq!(seen, Face::ALL);
+    }
+
+    #[test]
+    fn puts_the_date_on_the_last_line() {
+        struct Blank<'a>(&'a ClockConfig);
+        impl ClockFace for Blank<'_> {
+            fn render(&self, now: DateTime<Local>, area: Rect, buf: &mut Buffer) {
+                let rest = date_line(now, self.0, &plain(), area, buf);
+                assert_eq!(rest.height, area.height - 1);
+            }
+        }
+        let clock = ClockConfig::default();
+        draw(&Blank(&clock), evening(), 30, 2).assert_buffer_lines([
+            "                              ",
+            "  Thursday, October 15, 2026  ",
+        ]);
+    }
+}
@@ -0,0 +1,233 @@
+//! A word clock, "IT IS TWENTY PAST SEVEN", in five minute steps with a dot
+//! for each minute in between.
+
+use super::{ClockFace, centered, date_line};
+use crate::{config::ClockConfig, theme::Theme};
+use chrono::{DateTime, Local, Timelike};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+use serde::Deserialize;
+
+/// Language of the word clock.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    De,
+    Fr,
+}
+
+struct Phrases {
+    intro: &'static str,
+    /// One for every five minutes past the hour. `{h}` is the hour, `{n}`
+    /// the next one.
+    steps: [&'static str; 12],
+    /// Twelve, then one to eleven.
+    hours: [&'static str; 12],
+    /// Replacements for grammar the tables can't express.
+    fixes: &'static [(&'static str, &'static str)],
+}
+
+const ENGLISH: Phrases = Phrases {
+    intro: "IT IS",
+    steps: [
+        "{h} O'CLOCK",
+        "FIVE PAST {h}",
+        "TEN PAST {h}",
+        "QUARTER PAST {h}",
+        "TWENTY PAST {h}",
+        "TWENTY FIVE PAST {h}",
+        "HALF PAST {h}",
+        "TWENTY FIVE TO {n}",
+        "TWENTY TO {n}",
+        "QUARTER TO {n}",
+        "TEN TO {n}",
+        "FIVE TO {n}",
+    ],
+    hours: [
+        "TWELVE", "ONE", "TWO", "THREE", "FOUR", "FIVE", "SIX", "SEVEN", "EIGHT", "NINE", "TEN",
+        "ELEVEN",
+    ],
+    fixes: &[],
+};
+
+const GERMAN: Phrases = Phrases {
+    intro: "ES IST",
+    steps: [
+        "{h} UHR",
+        "FÜNF NACH {h}",
+        "ZEHN NACH {h}",
+        "VIERTEL NACH {h}",
+        "ZWANZIG NACH {h}",
+        "FÜNF VOR HALB {n}",
+        "HALB {n}",
+        "FÜNF NACH HALB {n}",
+        "ZWANZIG VOR {n}",
+        "VIERTEL VOR {n}",
+        "ZEHN VOR {n}",
+        "FÜNF VOR {n}",
+    ],
+    hours: [
+        "ZWÖLF", "EINS", "ZWEI", "DREI", "VIER", "FÜNF", "SECHS", "SIEBEN", "ACHT", "NEUN", "ZEHN",
+        "ELF",
+    ],
+    fixes: &[("EINS UHR", "EIN UHR")],
+};
+
+const FRENCH: Phrases = Phrases {
+    intro: "IL EST",
+    steps: [
+        "{h}",
+        "{h} CINQ",
+        "{h} DIX",
+        "{h} ET QUART",
+        "{h} VINGT",
+        "{h} VINGT-CINQ",
+        "{h} ET DEMIE",
+        "{n} MOINS VINGT-CINQ",
+        "{n} MOINS VINGT",
+        "{n} MOINS LE QUART",
+        "{n} MOINS DIX",
+        "{n} MOINS CINQ",
+    ],
+    hours: [
+        "DOUZE HEURES",
+        "UNE HEURE",
+        "DEUX HEURES",
+        "TROIS HEURES",
+        "QUATRE HEURES",
+        "CINQ HEURES",
+        "SIX HEURES",
+        "SEPT HEURES",
+        "HUIT HEURES",
+        "NEUF HEURES",
+        "DIX HEURES",
+        "ONZE HEURES",
+    ],
+    fixes: &[],
+};
+
+impl Language {
+    fn phrases(self) -> &'static Phrases {
+        match self {
+            Language::En => &ENGLISH,
+            Language::De => &GERMAN,
+            Language::Fr => &FRENCH,
+        }
+    }
+
+    /// The time rounded down to five minutes, in words.
+    pub fn phrase(self, hour: u32, minute: u32) -> String {
+        let phrases = self.phrases();
+        let step = phrases.steps[(minute / 5) as usize % 12];
+        let text = step
+            .replace("{h}", phrases.hours[(hour % 12) as usize])
+            .replace("{n}", phrases.hours[((hour + 1) % 12) as usize]);
+        let mut text = format!("{} {text}", phrases.intro);
+        for (from, to) in phrases.fixes {
+            text = text.replace(from, to);
+        }
+        text
+    }
+}
+
+pub struct Words<'a> {
+    pub clock: &'a ClockConfig,
+    pub colors: &'a Theme,
+}
+
+impl ClockFace for Words<'_> {
+    fn render(&self, now: DateTime<Local>, area: Rect, buf: &mut Buffer) {
+        let area = date_line(now, self.clock, self.colors, area, buf);
+        let phrase = self.clock.language.phrase(now.hour(), now.minute());
+        let style = Style::default()
+            .fg(self.colors.accent)
+            .add_modifier(Modifier::BOLD);
+        let mut lines: Vec<Line> = wrap(&phrase, area.width as usize)
+            .into_iter()
+            .map(|line| Line::from(Span::styled(line, style)))
+            .collect();
+
+        let past = (now.minute() % 5) as usize;
+        let dots: Vec<Span> = (0..4)
+            .map(|dot| match dot < past {
+                true => Span::styled("● ", Style::default().fg(self.colors.accent)),
+                false => Span::styled("· ", Style::default().fg(self.colors.dim)),
+            })
+            .collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(dots));
+        centered(lines, area, buf);
+    }
+}
+
+/// Breaks `text` into lines of at most `width` columns between words.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split(' ') {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::face::tests::{draw, evening, plain};
+
+    #[test]
+    fn says_the_time_in_each_language() {
+        assert_eq!(Language::En.phrase(19, 25), "IT IS TWENTY FIVE PAST SEVEN");
+        assert_eq!(Language::En.phrase(0, 58), "IT IS FIVE TO ONE");
+        assert_eq!(Language::En.phrase(12, 3), "IT IS TWELVE O'CLOCK");
+        assert_eq!(Language::De.phrase(19, 25), "ES IST FÜNF VOR HALB ACHT");
+        assert_eq!(Language::De.phrase(13, 0), "ES IST EIN UHR");
+        assert_eq!(Language::De.phrase(0, 30), "ES IST HALB EINS");
+        assert_eq!(Language::Fr.phrase(19, 25), "IL EST SEPT HEURES VINGT-CINQ");
+        assert_eq!(
+            Language::Fr.phrase(10, 45),
+            "IL EST ONZE HEURES MOINS LE QUART"
+        );
+    }
+
+    #[test]
+    fn wraps_between_words() {
+        assert_eq!(
+            wrap("IT IS TWENTY FIVE PAST SEVEN", 12),
+            ["IT IS TWENTY", "FIVE PAST", "SEVEN"]
+        );
+    }
+
+    #[test]
+    fn draws_the_phrase_and_minute_dots() {
+        let clock = ClockConfig::default();
+        let colors = plain();
+        let face = Words {
+            clock: &clock,
+            colors: &colors,
+        };
+        let now = evening() + chrono::TimeDelta::minutes(2);
+        let mut expected = Buffer::with_lines([
+            "                              ",
+            " IT IS TWENTY FIVE PAST SEVEN ",
+            "                              ",
+            "           ● ● · ·            ",
+            "                              ",
+            "                              ",
+            "  Thursday, October 15, 2026  ",
+        ]);
+        expected.set_style(Rect::new(1, 1, 28, 1), Modifier::BOLD);
+        draw(&face, now, 30, 7).assert_buffer(&expected);
+    }
+}
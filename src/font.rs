@@ -0,0 +1,677 @@
+//! Fonts for the big clock: the built-in ones and FIGlet `.flf` files.
+
+use anyhow::{Context, Result, bail};
+use std::{collections::HashMap, fs, path::Path};
+
+pub const BUILT_IN: [&str; 5] = ["block", "seven-segment", "thin", "braille", "half-block"];
+
+/// Big glyphs, all as tall as the font. Digits share one width so the clock
+/// does not shift sideways as the time changes.
+pub struct Font {
+    height: usize,
+    glyphs: HashMap<char, Vec<String>>,
+    /// Blank columns between glyphs.
+    spacing: usize,
+    /// Drawn in full blocks only, so it can be scaled up without looking
+    /// broken.
+    scalable: bool,
+}
+
+impl Font {
+    /// A built-in font by name, or a FIGlet font from a path ending in
+    /// `.flf`.
+    pub fn load(name: &str) -> Result<Font> {
+        let font = match name {
+            "block" => Font::from_table(BLOCK, 1),
+            "seven-segment" => seven_segment(),
+            "thin" => Font::from_table(THIN, 1),
+            "braille" => braille(),
+            "half-block" => half_block(),
+            path if path.ends_with(".flf") => {
+                let path = Path::new(path);
+                let text = fs::read_to_string(path)
+                    .with_context(|| format!("cannot read {}", path.display()))?;
+                figlet(&text).with_context(|| format!("{} is not a FIGlet font", path.display()))?
+            }
+            _ => bail!(
+                "Unknown font \"{name}\", use {} or a .flf file",
+                BUILT_IN.join(", ")
+            ),
+        };
+        Ok(font)
+    }
+
+    /// Small digits for seconds next to a font of `height` lines, if there
+    /// is one that is shorter.
+    pub fn smaller_than(height: usize) -> Option<Font> {
+        if height > SMALL[0].1.len() {
+            Some(Font::from_table(SMALL, 1))
+        } else if height > 2 {
+            Some(braille())
+        } else {
+            None
+        }
+    }
+
+    fn new(height: usize, mut glyphs: HashMap<char, Vec<String>>, spacing: usize) -> Font {
+        for glyph in glyphs.values_mut() {
+            glyph.resize(height, String::new());
+            let width = glyph
+                .iter()
+                .map(|line| line.chars().count())
+                .max()
+                .unwrap_or(0);
+            for line in glyph.iter_mut() {
+                pad(line, 0, width);
+            }
+        }
+        let digit_width = glyphs
+            .iter()
+            .filter(|(ch, _)| ch.is_ascii_digit())
+            .map(|(_, glyph)| width(glyph))
+            .max()
+            .unwrap_or(0);
+        for (_, glyph) in glyphs.iter_mut().filter(|(ch, _)| ch.is_ascii_digit()) {
+            let left = (digit_width - width(glyph)) / 2;
+            for line in glyph.iter_mut() {
+                pad(line, left, digit_width);
+            }
+        }
+        let scalable = glyphs
+            .values()
+            .flatten()
+            .all(|line| line.chars().all(|ch| ch == ' ' || ch == '█'));
+        Font {
+            height,
+            glyphs,
+            spacing,
+            scalable,
+        }
+    }
+
+    fn from_table(table: &[(char, &[&str])], spacing: usize) -> Font {
+        let height = table.iter().map(|(_, rows)| rows.len()).max().unwrap_or(0);
+        let glyphs = table
+            .iter()
+            .map(|(ch, rows)| (*ch, rows.iter().map(|row| row.to_string()).collect()))
+            .collect();
+        Font::new(height, glyphs, spacing)
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn scalable(&self) -> bool {
+        self.scalable
+    }
+
+    /// Whether every character of `text` has a glyph.
+    pub fn covers(&self, text: &str) -> bool {
+        text.chars().all(|ch| self.glyphs.contains_key(&ch))
+    }
+
+    /// Draws `text`, leaving colons dark unless `colon` is set.
+    pub fn render(&self, text: &str, colon: bool) -> Vec<String> {
+        let blank = self.glyphs.get(&'0').map_or(1, |glyph| width(glyph));
+        let mut lines = vec![String::new(); self.height];
+        for (i, ch) in text.chars().enumerate() {
+            let glyph = self.glyphs.get(&ch);
+            for (row, line) in lines.iter_mut().enumerate() {
+                if i > 0 {
+                    line.push_str(&" ".repeat(self.spacing));
+                }
+                match glyph {
+                    // A blinking colon keeps its place while dark.
+                    Some(glyph) if ch == ':' && !colon => {
+                        line.push_str(&" ".repeat(glyph[row].chars().count()))
+                    }
+                    Some(glyph) => line.push_str(&glyph[row]),
+                    None => line.push_str(&" ".repeat(blank)),
+                }
+            }
+        }
+        lines
+    }
+}
+
+/// Blows `lines` up `factor` times in both directions.
+pub fn scale(lines: Vec<String>, factor: usize) -> Vec<String> {
+    if factor == 1 {
+        return lines;
+    }
+    lines
+        .into_iter()
+        .flat_map(|line| {
+            let wide: String = line
+                .chars()
+                .flat_map(|ch| std::iter::repeat_n(ch, factor))
+                .collect();
+            std::iter::repeat_n(wide, factor)
+        })
+        .collect()
+}
+
+fn width(glyph: &[String]) -> usize {
+    glyph.first().map_or(0, |line| line.chars().count())
+}
+
+/// Pads `line` with `left` spaces before it and as many after as it takes
+/// to fill `width` columns.
+fn pad(line: &mut String, left: usize, width: usize) {
+    line.insert_str(0, &" ".repeat(left));
+    let count = line.chars().count();
+    line.push_str(&" ".repeat(width.saturating_sub(count)));
+}
+
+/// Reads a FIGlet font. Only the ASCII characters are used; the header is
+/// `flf2a<hardblank> <height> <baseline> <max length> <old layout>
+/// <comment lines> ...`.
+fn figlet(text: &str) -> Result<Font> {
+    let mut lines = text.lines();
+    let header = lines
+        .next()
+        .and_then(|line| line.strip_prefix("flf2a"))
+        .context("missing flf2a header")?;
+    let mut header = header.chars();
+    let hardblank = header.next().context("missing hardblank")?;
+    let fields = header
+        .as_str()
+        .split_whitespace()
+        .take(5)
+        .map(|field| field.parse::<i64>())
+        .collect::<Result<Vec<_>, _>>()
+        .context("bad header")?;
+    let [height, _baseline, _max_length, _old_layout, comments] = fields[..] else {
+        bail!("header is too short");
+    };
+    if !(1..=40).contains(&height) {
+        bail!("font height {height} is out of range");
+    }
+    let height = height as usize;
+    let mut lines = lines.skip(comments.max(0) as usize);
+
+    let mut glyphs = HashMap::new();
+    'chars: for ch in ' '..='~' {
+        let mut glyph = Vec::with_capacity(height);
+        for _ in 0..height {
+            let Some(line) = lines.next() else {
+                break 'chars;
+            };
+            // Every line ends in an end mark, the last one of a glyph in two.
+            let line = line.trim_end();
+            let line = match line.chars().last() {
+                Some(mark) => line.trim_end_matches(mark),
+                None => line,
+            };
+            glyph.push(line.replace(hardblank, " "));
+        }
+        glyphs.insert(ch, glyph);
+    }
+    if let Some(missing) = ('0'..='9').chain([':']).find(|ch| !glyphs.contains_key(ch)) {
+        bail!("no glyph for '{missing}'");
+    }
+    Ok(Font::new(height, glyphs, 0))
+}
+
+/// Segments that light up, bits 0 to 6 for the top, the upper right, lower
+/// right, bottom, lower left, upper left and middle.
+const SEGMENTS: &[(char, u8)] = &[
+    ('0', 0b0111111),
+    ('1', 0b0000110),
+    ('2', 0b1011011),
+    ('3', 0b1001111),
+    ('4', 0b1100110),
+    ('5', 0b1101101),
+    ('6', 0b1111101),
+    ('7', 0b0000111),
+    ('8', 0b1111111),
+    ('9', 0b1101111),
+    ('A', 0b1110111),
+    ('P', 0b1110011),
+];
+
+fn seven_segment() -> Font {
+    let lit = |segments: u8, bit: u8| segments & (1 << bit) != 0;
+    let bar = |on: bool| if on { " ━━━━ " } else { "      " }.to_string();
+    let sides = |left: bool, right: bool| {
+        format!(
+            "{}    {}",
+            if left { '┃' } else { ' ' },
+            if right { '┃' } else { ' ' }
+        )
+    };
+    let mut glyphs: HashMap<char, Vec<String>> = SEGMENTS
+        .iter()
+        .map(|&(ch, segments)| {
+            let upper = sides(lit(segments, 5), lit(segments, 1));
+            let lower = sides(lit(segments, 4), lit(segments, 2));
+            let glyph = vec![
+                bar(lit(segments, 0)),
+                upper.clone(),
+                upper,
+                bar(lit(segments, 6)),
+                lower.clone(),
+                lower,
+                bar(lit(segments, 3)),
+            ];
+            (ch, glyph)
+        })
+        .collect();
+    let colon = [" ", " ", "•", " ", "•", " ", " "];
+    glyphs.insert(':', colon.map(String::from).to_vec());
+    glyphs.insert(' ', vec![" ".to_string(); 7]);
+    Font::new(7, glyphs, 1)
+}
+
+/// Turns a pixel font into text with `cell` characters, each covering
+/// `columns` by `rows` pixels.
+fn from_pixels(
+    columns: usize,
+    rows: usize,
+    cell: impl Fn(&dyn Fn(usize, usize) -> bool) -> char,
+) -> Font {
+    let glyphs = PIXELS
+        .iter()
+        .map(|(ch, bitmap)| {
+            let lit = |x: usize, y: usize| {
+                bitmap
+                    .get(y)
+                    .and_then(|row| row.as_bytes().get(x))
+                    .is_some_and(|pixel| *pixel == b'#')
+            };
+            let width = bitmap[0].len().div_ceil(columns);
+            let glyph = (0..bitmap.len().div_ceil(rows))
+                .map(|row| {
+                    (0..width)
+                        .map(|column| cell(&|x, y| lit(column * columns + x, row * rows + y)))
+                        .collect()
+                })
+                .collect();
+            (*ch, glyph)
+        })
+        .collect();
+    Font::new(PIXELS[0].1.len().div_ceil(rows), glyphs, 1)
+}
+
+/// Two pixels a character, one above the other.
+fn half_block() -> Font {
+    from_pixels(1, 2, |lit| match (lit(0, 0), lit(0, 1)) {
+        (true, true) => '█',
+        (true, false) => '▀',
+        (false, true) => '▄',
+        (false, false) => ' ',
+    })
+}
+
+/// Eight pixels a character, two across and four down.
+fn braille() -> Font {
+    // Dot bits in the order the Unicode braille block numbers them.
+    const DOTS: [(usize, usize); 8] = [
+        (0, 0),
+        (0, 1),
+        (0, 2),
+        (1, 0),
+        (1, 1),
+        (1, 2),
+        (0, 3),
+        (1, 3),
+    ];
+    from_pixels(2, 4, |lit| {
+        let bits = DOTS
+            .iter()
+            .enumerate()
+            .filter(|(_, (x, y))| lit(*x, *y))
+            .fold(0, |bits, (bit, _)| bits | 1 << bit);
+        match bits {
+            0 => ' ',
+            bits => char::from_u32(0x2800 + bits).unwrap_or(' '),
+        }
+    })
+}
+
+const BLOCK: &[(char, &[&str])] = &[
+    (
+        '0',
+        &[
+            "███████",
+            "██   ██",
+            "██   ██",
+            "██   ██",
+            "██   ██",
+            "██   ██",
+            "███████",
+        ],
+    ),
+    (
+        '1',
+        &[
+            "   ██  ",
+            "  ███  ",
+            "   ██  ",
+            "   ██  ",
+            "   ██  ",
+            "   ██  ",
+            "███████",
+        ],
+    ),
+    (
+        '2',
+        &[
+            "███████",
+            "     ██",
+            "     ██",
+            "███████",
+            "██     ",
+            "██     ",
+            "███████",
+        ],
+    ),
+    (
+        '3',
+        &[
+            "███████",
+            "     ██",
+            "     ██",
+            "███████",
+            "     ██",
+            "     ██",
+            "███████",
+        ],
+    ),
+    (
+        '4',
+        &[
+            "██   ██",
+            "██   ██",
+            "██   ██",
+            "███████",
+            "     ██",
+            "     ██",
+            "     ██",
+        ],
+    ),
+    (
+        '5',
+        &[
+            "███████",
+            "██     ",
+            "██     ",
+            "███████",
+            "     ██",
+            "     ██",
+            "███████",
+        ],
+    ),
+    (
+        '6',
+        &[
+            "███████",
+            "██     ",
+            "██     ",
+            "███████",
+            "██   ██",
+            "██   ██",
+            "███████",
+        ],
+    ),
+    (
+        '7',
+        &[
+            "███████",
+            "     ██",
+            "     ██",
+            "     ██",
+            "     ██",
+            "     ██",
+            "     ██",
+        ],
+    ),
+    (
+        '8',
+        &[
+            "███████",
+            "██   ██",
+            "██   ██",
+            "███████",
+            "██   ██",
+            "██   ██",
+            "███████",
+        ],
+    ),
+    (
+        '9',
+        &[
+            "███████",
+            "██   ██",
+            "██   ██",
+            "███████",
+            "     ██",
+            "     ██",
+            "███████",
+        ],
+    ),
+    (
+        'A',
+        &[
+            " █████ ",
+            "██   ██",
+            "██   ██",
+            "███████",
+            "██   ██",
+            "██   ██",
+            "██   ██",
+        ],
+    ),
+    (
+        'P',
+        &[
+            "██████ ",
+            "██   ██",
+            "██   ██",
+            "██████ ",
+            "██     ",
+            "██     ",
+            "██     ",
+        ],
+    ),
+    (
+        'M',
+        &[
+            "██   ██",
+            "███ ███",
+            "██ █ ██",
+            "██   ██",
+            "██   ██",
+            "██   ██",
+            "██   ██",
+        ],
+    ),
+    (
+        ':',
+        &[
+            "       ",
+            "   ██  ",
+            "   ██  ",
+            "       ",
+            "   ██  ",
+            "   ██  ",
+            "       ",
+        ],
+    ),
+    (' ', &[" "; 7]),
+];
+
+const THIN: &[(char, &[&str])] = &[
+    ('0', &["┌───┐", "│   │", "│   │", "│   │", "└───┘"]),
+    ('1', &["╶─┐  ", "  │  ", "  │  ", "  │  ", "╶─┴─╴"]),
+    ('2', &["╶───┐", "    │", "┌───┘", "│    ", "└───╴"]),
+    ('3', &["╶───┐", "    │", " ───┤", "    │", "╶───┘"]),
+    ('4', &["╷   ╷", "│   │", "└───┤", "    │", "    ╵"]),
+    ('5', &["┌───╴", "│    ", "└───┐", "    │", "╶───┘"]),
+    ('6', &["┌───╴", "│    ", "├───┐", "│   │", "└───┘"]),
+    ('7', &["╶───┐", "    │", "    │", "    │", "    ╵"]),
+    ('8', &["┌───┐", "│   │", "├───┤", "│   │", "└───┘"]),
+    ('9', &["┌───┐", "│   │", "└───┤", "    │", "╶───┘"]),
+    ('A', &["┌───┐", "│   │", "├───┤", "│   │", "╵   ╵"]),
+    ('P', &["┌───┐", "│   │", "├───┘", "│    ", "╵    "]),
+    ('M', &["┌─┬─┐", "│ │ │", "│ ╵ │", "│   │", "╵   ╵"]),
+    (':', &[" ", "•", " ", "•", " "]),
+    (' ', &[" "; 5]),
+];
+
+/// Seconds beside the big clock.
+const SMALL: &[(char, &[&str])] = &[
+    ('0', &["███", "█ █", "█ █", "█ █", "███"]),
+    ('1', &[" █ ", "██ ", " █ ", " █ ", "███"]),
+    ('2', &["███", "  █", "███", "█  ", "███"]),
+    ('3', &["███", "  █", "███", "  █", "███"]),
+    ('4', &["█ █", "█ █", "███", "  █", "  █"]),
+    ('5', &["███", "█  ", "███", "  █", "███"]),
+    ('6', &["███", "█  ", "███", "█ █", "███"]),
+    ('7', &["███", "  █", "  █", "  █", "  █"]),
+    ('8', &["███", "█ █", "███", "█ █", "███"]),
+    ('9', &["███", "█ █", "███", "  █", "███"]),
+];
+
+/// A 5 by 7 dot matrix for the braille and half-block fonts.
+const PIXELS: &[(char, [&str; 7])] = &[
+    (
+        '0',
+        [
+            ".###.", "#...#", "#..##", "#.#.#", "##..#", "#...#", ".###.",
+        ],
+    ),
+    (
+        '1',
+        [
+            "..#..", ".##..", "..#..", "..#..", "..#..", "..#..", ".###.",
+        ],
+    ),
+    (
+        '2',
+        [
+            ".###.", "#...#", "....#", "...#.", "..#..", ".#...", "#####",
+        ],
+    ),
+    (
+        '3',
+        [
+            "#####", "...#.", "..#..", "...#.", "....#", "#...#", ".###.",
+        ],
+    ),
+    (
+        '4',
+        [
+            "...#.", "..##.", ".#.#.", "#..#.", "#####", "...#.", "...#.",
+        ],
+    ),
+    (
+        '5',
+        [
+            "#####", "#....", "####.", "....#", "....#", "#...#", ".###.",
+        ],
+    ),
+    (
+        '6',
+        [
+            "..##.", ".#...", "#....", "####.", "#...#", "#...#", ".###.",
+        ],
+    ),
+    (
+        '7',
+        [
+            "#####", "....#", "...#.", "..#..", ".#...", ".#...", ".#...",
+        ],
+    ),
+    (
+        '8',
+        [
+            ".###.", "#...#", "#...#", ".###.", "#...#", "#...#", ".###.",
+        ],
+    ),
+    (
+        '9',
+        [
+            ".###.", "#...#", "#...#", ".####", "....#", "...#.", ".##..",
+        ],
+    ),
+    (
+        'A',
+        [
+            ".###.", "#...#", "#...#", "#####", "#...#", "#...#", "#...#",
+        ],
+    ),
+    (
+        'P',
+        [
+            "####.", "#...#", "#...#", "####.", "#....", "#....", "#....",
+        ],
+    ),
+    (
+        'M',
+        [
+            "#...#", "##.##", "#.#.#", "#.#.#", "#...#", "#...#", "#...#",
+        ],
+    ),
+    (':', [".", ".", "#", ".", "#", ".", "."]),
+    (' ', [".", ".", ".", ".", ".", ".", "."]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits_share_one_width() {
+        for name in BUILT_IN {
+            let font = Font::load(name).unwrap();
+            let widths: Vec<usize> = ('0'..='9')
+                .map(|digit| font.render(&digit.to_string(), true)[0].chars().count())
+                .collect();
+            assert!(widths.iter().all(|w| *w == widths[0]), "{name}: {widths:?}");
+        }
+    }
+
+    #[test]
+    fn blinking_colon_keeps_its_width() {
+        let font = Font::load("block").unwrap();
+        let lit = font.render("12:34", true);
+        let dark = font.render("12:34", false);
+        let blocks = |lines: &[String]| lines.concat().matches('█').count();
+        assert_eq!(lit[1].chars().count(), dark[1].chars().count());
+        assert_eq!(blocks(&lit) - blocks(&dark), 8);
+    }
+
+    #[test]
+    fn scales_block_fonts_only() {
+        assert!(Font::load("block").unwrap().scalable());
+        assert!(!Font::load("thin").unwrap().scalable());
+        let lines = vec!["█ ".to_string(), " █".to_string()];
+        assert_eq!(scale(lines, 2), ["██  ", "██  ", "  ██", "  ██"]);
+    }
+
+    #[test]
+    fn draws_pixels_as_braille_and_half_blocks() {
+        assert_eq!(braille().render("1", true), ["⠐⡇ ", "⠠⠧ "]);
+        assert_eq!(half_block().render("1", true)[0], " ▄█  ");
+    }
+
+    #[test]
+    fn reads_figlet_fonts() {
+        let mut text = "flf2a$ 2 2 4 -1 1\nA two line test font\n".to_string();
+        for ch in ' '..='~' {
+            text.push_str(&format!("{ch}$@\n|{ch}@@\n"));
+        }
+        let font = figlet(&text).unwrap();
+        assert_eq!(font.height(), 2);
+        assert_eq!(font.render("1:2", true), ["1 : 2 ", "|1|:|2"]);
+    }
+
+    #[test]
+    fn rejects_figlet_fonts_without_digits() {
+        let text = "flf2a$ 1 1 4 -1 0\n $@\n!@\n";
+        assert_eq!(figlet(text).err().unwrap().to_string(), "no glyph for '0'");
+        assert!(figlet("not a font").is_err());
+    }
+}
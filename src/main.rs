@@ -1,5 +1,5 @@
 use anyhow::Result;
-use chrono::{Local, DateTime};
+use chrono::{DateTime, Local, TimeDelta};
 use clap::Parser;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
@@ -9,224 +9,789 @@ use crossterm::{
 use ratatui::{
     backend::{Backend, CrosstermBackend},
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Paragraph},
+    widgets::{
+        block::Title, Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Sparkline, Table, Wrap,
+    },
     Terminal,
 };
 use std::{
-    io,
-    time::Duration,
+    io::{self, Write},
+    path::PathBuf,
+    time::Instant,
 };
-use tokio::time::sleep;
+
+mod alarm;
+mod audio;
+mod color;
+mod config;
+mod face;
+mod font;
+mod paths;
+mod playlist;
+mod radio;
+mod schedule;
+mod settings;
+mod sleep;
+mod store;
+mod theme;
+mod weather;
+
+use alarm::{AlarmList, AlarmSpec, Outcome, Ringing};
+use audio::{Output, Player, Sink, Sound, Volume};
+use color::ColorMode;
+use config::Config;
+use face::{Analog, Binary, ClockFace, Digital, Epoch, Face, Words};
+use font::Font;
+use radio::{MAX_PRESETS, Presets, Radio, Station, StreamInfo, StreamState};
+use schedule::Schedule;
+use settings::Settings;
+use store::Store;
+use theme::Theme;
+use tokio::sync::watch;
+use weather::{Action, Report, Rule, Sky, Units, Weather};
 
 #[derive(Parser)]
 #[command(name = "clockradio")]
 #[command(about = "A simple TUI clock radio with weather and alarm")]
-struct Cli {}
+struct Cli {
+    /// Read settings from this file instead of the XDG config directory
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+    /// Write alarm sounds to this file as raw 16-bit stereo PCM at 44.1 kHz
+    /// instead of playing them
+    #[arg(long, value_name = "PATH")]
+    pcm_out: Option<PathBuf>,
+    /// Replace the radio presets with the stations of an M3U or PLS
+    /// playlist, then exit
+    #[arg(long, value_name = "PLAYLIST")]
+    import_stations: Option<PathBuf>,
+    /// Write the radio presets to an M3U playlist, then exit
+    #[arg(long, value_name = "PATH")]
+    export_stations: Option<PathBuf>,
+    /// Colors the terminal can show, instead of guessing from NO_COLOR,
+    /// COLORTERM and TERM
+    #[arg(long, value_enum, value_name = "MODE")]
+    colors: Option<ColorMode>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Clock,
+    AlarmList,
+    EditAlarm,
+    /// Typing a custom sleep timer.
+    SleepTimer,
+    Forecast,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum EditorField {
+    Time,
+    Label,
+    Repeat,
+    Sound,
+    Volume,
+    Weather,
+}
+
+struct AlarmEditor {
+    /// Alarm being edited, `None` when adding a new one.
+    id: Option<u32>,
+    time: String,
+    label: String,
+    repeat: String,
+    sound: String,
+    volume: String,
+    weather: String,
+    field: EditorField,
+    error: Option<String>,
+    /// Mode to return to once the editor closes.
+    back: Mode,
+}
+
+impl AlarmEditor {
+    fn new(back: Mode) -> AlarmEditor {
+        AlarmEditor {
+            id: None,
+            time: String::new(),
+            label: String::new(),
+            repeat: String::new(),
+            sound: String::new(),
+            volume: String::new(),
+            weather: String::new(),
+            field: EditorField::Time,
+            error: None,
+            back,
+        }
+    }
+
+    fn input(&mut self) -> &mut String {
+        match self.field {
+            EditorField::Time => &mut self.time,
+            EditorField::Label => &mut self.label,
+            EditorField::Repeat => &mut self.repeat,
+            EditorField::Sound => &mut self.sound,
+            EditorField::Volume => &mut self.volume,
+            EditorField::Weather => &mut self.weather,
+        }
+    }
+}
+
+/// What the first ringing alarm is playing. Players are only held to keep
+/// them running.
+enum AlarmSound {
+    Player { _player: Player },
+    /// A station preset; `heard` is when it last played.
+    Station { radio: Radio, heard: Instant },
+    /// The tone, because the alarm's station did not play.
+    Fallback { _player: Player },
+}
 
 struct App {
     should_quit: bool,
-    alarm_time: Option<DateTime<Local>>,
-    show_alarm_dialog: bool,
-    alarm_input: String,
+    mode: Mode,
+    alarms: AlarmList,
+    settings: Settings,
+    selected: usize,
+    editor: AlarmEditor,
+    /// Alarms currently ringing, the first one is shown.
+    ringing: Vec<Ringing>,
+    /// Sound of the first ringing alarm, with the alarm it belongs to.
+    player: Option<(u32, AlarmSound)>,
+    radio: Option<Radio>,
+    stations: Presets,
+    /// Station 'r' turns back on.
+    last_station: Option<Station>,
+    /// Length of the running sleep timer and when it ends.
+    sleep: Option<(u32, DateTime<Local>)>,
+    sleep_input: String,
+    weather: watch::Receiver<Weather>,
+    font: Font,
+    /// Smaller digits for seconds when the clock is too wide.
+    seconds_font: Option<Font>,
+    /// What 't' switches between, fitted to the terminal.
+    themes: Vec<Theme>,
+    color_mode: ColorMode,
+    config: Config,
+    store: Store,
+    /// Alarms or settings changed since the last save.
+    dirty: bool,
+    /// Problem to show in the bottom bar.
+    status: Option<String>,
     animation_frame: u32,
 }
 
 impl App {
-    fn new() -> App {
-        App {
+    fn new(config: Config, store: Store, color_mode: ColorMode) -> Result<App> {
+        let state = store.load()?;
+        let mut alarms = state.alarms;
+        alarms.catch_up(Local::now());
+        let font = Font::load(&config.clock.font)?;
+        let mut themes = theme::load_all(paths::config_dir().map(|dir| dir.join("themes")).as_deref())?;
+        if let Some(custom) = &config.colors {
+            themes.insert(0, Theme { name: "custom".to_string(), ..custom.clone() });
+        }
+        let themes = themes.iter().map(|theme| theme.fit(color_mode)).collect();
+
+        Ok(App {
             should_quit: false,
-            alarm_time: None,
-            show_alarm_dialog: false,
-            alarm_input: String::new(),
+            mode: Mode::Clock,
+            alarms,
+            settings: state.settings,
+            selected: 0,
+            editor: AlarmEditor::new(Mode::Clock),
+            ringing: Vec::new(),
+            player: None,
+            radio: None,
+            stations: state.stations,
+            last_station: None,
+            sleep: None,
+            sleep_input: String::new(),
+            weather: weather::spawn(config.weather.clone()),
+            seconds_font: Font::smaller_than(font.height()),
+            font,
+            themes,
+            color_mode,
+            config,
+            store,
+            dirty: true,
+            status: None,
             animation_frame: 0,
+        })
+    }
+
+    fn save(&mut self) {
+        match self.store.save(&self.settings, &self.alarms, &self.stations) {
+            Ok(()) => {
+                self.dirty = false;
+                self.status = None;
+            }
+            Err(err) => self.status = Some(format!("Saving failed: {err:#}")),
+        }
+    }
+
+    /// The picked theme, or the first one if it's gone.
+    fn colors(&self) -> &Theme {
+        let picked = self.settings.theme.as_ref();
+        self.themes
+            .iter()
+            .find(|theme| Some(&theme.name) == picked)
+            .unwrap_or(&self.themes[0])
+    }
+
+    fn next_theme(&mut self) {
+        let index = self.themes.iter().position(|theme| theme.name == self.colors().name).unwrap_or(0);
+        self.settings.theme = Some(self.themes[(index + 1) % self.themes.len()].name.clone());
+        self.dirty = true;
+    }
+
+    fn selected_alarm_id(&self) -> Option<u32> {
+        self.alarms.iter().nth(self.selected).map(|a| a.id)
+    }
+
+    fn open_editor(&mut self, id: Option<u32>) {
+        let mut editor = AlarmEditor::new(self.mode);
+        if let Some(alarm) = id.and_then(|id| self.alarms.get(id)) {
+            editor.id = Some(alarm.id);
+            editor.time = alarm.time.format(self.config.clock.input_time_format()).to_string();
+            editor.label = alarm.label.clone();
+            editor.repeat = alarm.schedule.to_string();
+            editor.sound = alarm.sound.to_string();
+            editor.volume = alarm.volume.to_string();
+            editor.weather = alarm.rule.as_ref().map(Rule::to_string).unwrap_or_default();
+        }
+        self.editor = editor;
+        self.mode = Mode::EditAlarm;
+    }
+
+    /// Starts alarms that are due and gives up on ones nobody stopped.
+    fn check_alarms(&mut self, now: DateTime<Local>) {
+        if let Some(report) = self.weather.borrow().report()
+            && self.alarms.apply_weather(now, report, self.config.weather.units)
+        {
+            self.dirty = true;
+        }
+        if self.alarms.pass_skipped(now) {
+            self.dirty = true;
+        }
+        for alarm_id in self.alarms.take_due(now) {
+            self.dirty = true;
+            // The alarm takes over from the radio.
+            self.radio = None;
+            if !self.ringing.iter().any(|r| r.alarm_id == alarm_id) {
+                self.ringing.push(Ringing { alarm_id, since: now });
+            }
+        }
+
+        let timeout = TimeDelta::minutes(i64::from(self.settings.ring_timeout_minutes));
+        if self.alarms.time_out(&mut self.ringing, now, timeout) {
+            self.dirty = true;
+        }
+        self.update_sound();
+    }
+
+    /// Keeps the sound in line with the alarm shown as ringing.
+    fn update_sound(&mut self) {
+        let ringing = self.ringing.first().map(|r| r.alarm_id);
+        if self.player.as_ref().map(|(id, _)| *id) == ringing {
+            self.check_station();
+            return;
+        }
+        self.player = None;
+
+        let Some(alarm) = ringing.and_then(|id| self.alarms.get(id)) else {
+            return;
+        };
+        let (id, sound, volume) = (alarm.id, alarm.sound.clone(), alarm.volume);
+        let Some(sink) = self.open_sink() else {
+            return;
+        };
+        let playing = match sound {
+            Sound::Preset(slot) => match self.stations.get(slot) {
+                Some(station) => AlarmSound::Station {
+                    radio: Radio::start(station, volume, sink),
+                    heard: Instant::now(),
+                },
+                None => AlarmSound::Fallback {
+                    _player: Player::start(audio::alarm_source(&Sound::Tone, volume), sink),
+                },
+            },
+            sound => AlarmSound::Player {
+                _player: Player::start(audio::alarm_source(&sound, volume), sink),
+            },
+        };
+        self.player = Some((id, playing));
+    }
+
+    /// Rings the tone instead of a station that is not playing, so a dead
+    /// stream never means a silent alarm.
+    fn check_station(&mut self) {
+        let Some((id, AlarmSound::Station { radio, heard })) = &mut self.player else {
+            return;
+        };
+        let state = radio.info().state;
+        if state == StreamState::Playing {
+            *heard = Instant::now();
+        }
+        if !state.should_fall_back(heard.elapsed(), self.config.radio.alarm_timeout()) {
+            return;
+        }
+        let id = *id;
+        // Release the station's output before opening one for the tone.
+        self.player = None;
+        let volume = self.alarms.get(id).map(|alarm| alarm.volume).unwrap_or_default();
+        if let Some(sink) = self.open_sink() {
+            let tone = Player::start(audio::alarm_source(&Sound::Tone, volume), sink);
+            self.player = Some((id, AlarmSound::Fallback { _player: tone }));
+        }
+    }
+
+    /// Opens the sound output, or `None` when there is none.
+    fn open_sink(&mut self) -> Option<Box<dyn Sink>> {
+        match audio::open_output(&self.config.audio) {
+            Ok(sink) => sink,
+            Err(err) => {
+                self.status = Some(format!("No sound: {err:#}"));
+                None
+            }
         }
     }
 
+    fn toggle_radio(&mut self) {
+        if self.radio.take().is_some() {
+            return;
+        }
+        let station = self.last_station.clone().or_else(|| {
+            let configured = self.config.radio.station.as_ref().map(|url| Station {
+                name: String::new(),
+                url: url.clone(),
+            });
+            configured.or_else(|| self.stations.get(0).cloned())
+        });
+        match station {
+            Some(station) => self.play_station(station),
+            None => {
+                self.status = Some(
+                    "No station, import presets or set [radio] station in the config".to_string(),
+                );
+            }
+        }
+    }
+
+    fn play_preset(&mut self, slot: usize) {
+        match self.stations.get(slot) {
+            Some(station) => self.play_station(station.clone()),
+            None => self.status = Some(format!("No preset on key {}", radio::key_for_slot(slot))),
+        }
+    }
+
+    fn play_station(&mut self, station: Station) {
+        // Only one stream plays at a time.
+        self.radio = None;
+        match audio::open_output(&self.config.audio) {
+            Ok(Some(sink)) => {
+                self.radio = Some(Radio::start(&station, Volume::default(), sink));
+                self.last_station = Some(station);
+            }
+            Ok(None) => self.status = Some("No sound output for the radio".to_string()),
+            Err(err) => self.status = Some(format!("No sound: {err:#}")),
+        }
+    }
+
+    /// Moves the sleep timer to the next step, or turns it off after the
+    /// longest.
+    fn cycle_sleep(&mut self) {
+        let current = self.sleep.map(|(minutes, _)| minutes);
+        self.set_sleep(sleep::next_step(current));
+    }
+
+    /// Starts the sleep timer, turning the radio on if it is off.
+    fn set_sleep(&mut self, minutes: Option<u32>) {
+        self.sleep = minutes.map(|minutes| {
+            (minutes, Local::now() + TimeDelta::minutes(i64::from(minutes)))
+        });
+        match &self.radio {
+            Some(radio) => radio.set_volume(1.0),
+            None if minutes.is_some() => self.toggle_radio(),
+            None => {}
+        }
+    }
+
+    /// Fades the radio out as the sleep timer runs down, then stops it.
+    fn check_sleep(&mut self, now: DateTime<Local>) {
+        let Some((_, until)) = self.sleep else {
+            return;
+        };
+        match (sleep::volume(until - now), &self.radio) {
+            (None, _) => {
+                self.sleep = None;
+                self.radio = None;
+            }
+            (Some(volume), Some(radio)) => radio.set_volume(volume),
+            (Some(_), None) => {}
+        }
+    }
+
+    fn snooze(&self) -> TimeDelta {
+        TimeDelta::minutes(i64::from(self.settings.snooze_minutes))
+    }
+
+    fn stop_ringing(&mut self, outcome: Outcome) {
+        if !self.ringing.is_empty() {
+            let ringing = self.ringing.remove(0);
+            let snooze = self.snooze();
+            self.alarms.record(ringing.alarm_id, outcome, Local::now(), snooze);
+            self.dirty = true;
+            self.update_sound();
+        }
+    }
 
     fn handle_key_event(&mut self, key: KeyCode) {
+        if !self.ringing.is_empty() {
+            match key {
+                KeyCode::Char('d') | KeyCode::Enter | KeyCode::Esc => self.stop_ringing(Outcome::Dismissed),
+                KeyCode::Char('z') | KeyCode::Char(' ') => self.stop_ringing(Outcome::Snoozed),
+                _ => {}
+            }
+            return;
+        }
+
+        match self.mode {
+            Mode::Clock => self.handle_clock_key(key),
+            Mode::AlarmList => self.handle_list_key(key),
+            Mode::EditAlarm => self.handle_editor_key(key),
+            Mode::SleepTimer => self.handle_sleep_key(key),
+            Mode::Forecast => self.handle_forecast_key(key),
+        }
+    }
+
+    fn handle_clock_key(&mut self, key: KeyCode) {
         match key {
             KeyCode::Char('q') => self.should_quit = true,
-            KeyCode::Char('a') => self.show_alarm_dialog = true,
-            KeyCode::Esc => {
-                self.show_alarm_dialog = false;
-                self.alarm_input.clear();
+            KeyCode::Char('a') => self.open_editor(None),
+            KeyCode::Char('l') => self.mode = Mode::AlarmList,
+            KeyCode::Char('f') => self.mode = Mode::Forecast,
+            KeyCode::Char('c') => {
+                self.settings.face = self.settings.face.next();
+                self.dirty = true;
+            }
+            KeyCode::Char('t') => self.next_theme(),
+            KeyCode::Char('r') => self.toggle_radio(),
+            KeyCode::Char('s') => self.cycle_sleep(),
+            KeyCode::Char('S') => {
+                self.sleep_input.clear();
+                self.mode = Mode::SleepTimer;
+            }
+            KeyCode::Char(key) if key.is_ascii_digit() => {
+                if let Some(slot) = radio::slot_for_key(key) {
+                    self.play_preset(slot);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_forecast_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc | KeyCode::Char('f') | KeyCode::Char('q') => self.mode = Mode::Clock,
+            // The radio stays in reach while reading the forecast.
+            KeyCode::Char('r') | KeyCode::Char('s') => self.handle_clock_key(key),
+            KeyCode::Char(key) if key.is_ascii_digit() => self.handle_clock_key(KeyCode::Char(key)),
+            _ => {}
+        }
+    }
+
+    fn handle_list_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc | KeyCode::Char('l') | KeyCode::Char('q') => self.mode = Mode::Clock,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') if self.selected + 1 < self.alarms.len() => {
+                self.selected += 1;
+            }
+            KeyCode::Char('a') | KeyCode::Char('n') => self.open_editor(None),
+            KeyCode::Enter | KeyCode::Char('e') => {
+                if let Some(id) = self.selected_alarm_id() {
+                    self.open_editor(Some(id));
+                }
+            }
+            KeyCode::Char(' ') => {
+                if let Some(id) = self.selected_alarm_id() {
+                    self.alarms.toggle(id, Local::now());
+                    self.dirty = true;
+                }
+            }
+            KeyCode::Char('s') => {
+                if let Some(id) = self.selected_alarm_id() {
+                    self.alarms.toggle_skip(id, Local::now());
+                    self.dirty = true;
+                }
+            }
+            KeyCode::Char('+') => {
+                self.settings.snooze_minutes = (self.settings.snooze_minutes + 1).min(60);
+                self.dirty = true;
+            }
+            KeyCode::Char('-') => {
+                self.settings.snooze_minutes = self.settings.snooze_minutes.saturating_sub(1).max(1);
+                self.dirty = true;
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if let Some(id) = self.selected_alarm_id() {
+                    self.alarms.remove(id);
+                    self.dirty = true;
+                    self.selected = self.selected.min(self.alarms.len().saturating_sub(1));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_editor_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => self.mode = self.editor.back,
+            KeyCode::Tab => {
+                self.editor.field = match self.editor.field {
+                    EditorField::Time => EditorField::Label,
+                    EditorField::Label => EditorField::Repeat,
+                    EditorField::Repeat => EditorField::Sound,
+                    EditorField::Sound => EditorField::Volume,
+                    EditorField::Volume => EditorField::Weather,
+                    EditorField::Weather => EditorField::Time,
+                };
+            }
+            KeyCode::BackTab => {
+                self.editor.field = match self.editor.field {
+                    EditorField::Time => EditorField::Weather,
+                    EditorField::Label => EditorField::Time,
+                    EditorField::Repeat => EditorField::Label,
+                    EditorField::Sound => EditorField::Repeat,
+                    EditorField::Volume => EditorField::Sound,
+                    EditorField::Weather => EditorField::Volume,
+                };
             }
             KeyCode::Enter => {
-                if self.show_alarm_dialog {
-                    if let Ok(time) = chrono::NaiveTime::parse_from_str(&self.alarm_input, "%H:%M") {
-                        let now = Local::now();
-                        let mut alarm_date = now.date_naive();
-                        
-                        if time <= now.time() {
-                            alarm_date = alarm_date.succ_opt().unwrap_or(alarm_date);
+                let time = match alarm::parse_time(&self.editor.time) {
+                    Ok(time) => time,
+                    Err(err) => {
+                        self.editor.error = Some(err);
+                        return;
+                    }
+                };
+                let now = Local::now();
+                let schedule = match self.editor.id.and_then(|id| self.alarms.get(id)) {
+                    // Keep the anchor day of an unchanged interval.
+                    Some(alarm) if alarm.schedule.to_string() == self.editor.repeat.trim() => {
+                        alarm.schedule.clone()
+                    }
+                    _ => match Schedule::parse(&self.editor.repeat, now.date_naive()) {
+                        Ok(schedule) => schedule,
+                        Err(err) => {
+                            self.editor.error = Some(err);
+                            return;
                         }
-                        
-                        self.alarm_time = Some(alarm_date.and_time(time).and_local_timezone(Local).unwrap());
+                    },
+                };
+                let sound = match Sound::parse(&self.editor.sound) {
+                    Ok(sound) => sound,
+                    Err(err) => {
+                        self.editor.error = Some(err);
+                        return;
                     }
-                    self.show_alarm_dialog = false;
-                    self.alarm_input.clear();
-                }
+                };
+                let volume = match Volume::parse(&self.editor.volume) {
+                    Ok(volume) => volume,
+                    Err(err) => {
+                        self.editor.error = Some(err);
+                        return;
+                    }
+                };
+                let rule = match Rule::parse(&self.editor.weather) {
+                    Ok(rule) => rule,
+                    Err(err) => {
+                        self.editor.error = Some(err);
+                        return;
+                    }
+                };
+                let spec = AlarmSpec {
+                    label: self.editor.label.trim().to_string(),
+                    time,
+                    schedule,
+                    sound,
+                    volume,
+                    rule,
+                };
+                let id = match self.editor.id {
+                    Some(id) => {
+                        self.alarms.update(id, spec, now);
+                        id
+                    }
+                    None => self.alarms.add(spec, now),
+                };
+                self.selected = self.alarms.iter().position(|a| a.id == id).unwrap_or(0);
+                self.mode = self.editor.back;
+                self.dirty = true;
             }
             KeyCode::Backspace => {
-                if self.show_alarm_dialog {
-                    self.alarm_input.pop();
-                }
+                self.editor.input().pop();
             }
             KeyCode::Char(c) => {
-                if self.show_alarm_dialog {
-                    self.alarm_input.push(c);
+                self.editor.input().push(c);
+                self.editor.error = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_sleep_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => self.mode = Mode::Clock,
+            KeyCode::Enter => {
+                match sleep::parse_minutes(&self.sleep_input) {
+                    Ok(minutes) => {
+                        self.status = None;
+                        self.set_sleep(minutes);
+                    }
+                    Err(err) => {
+                        self.status = Some(err);
+                        return;
+                    }
                 }
+                self.mode = Mode::Clock;
+            }
+            KeyCode::Backspace => {
+                self.sleep_input.pop();
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() && self.sleep_input.len() < 3 => {
+                self.sleep_input.push(c);
             }
             _ => {}
         }
     }
 }
 
-fn get_ascii_digit(digit: char) -> Vec<&'static str> {
-    match digit {
-        '0' => vec![
-            "███████",
-            "██   ██",
-            "██   ██",
-            "██   ██",
-            "██   ██",
-            "██   ██",
-            "███████",
-        ],
-        '1' => vec![
-            "   ██  ",
-            "  ███  ",
-            "   ██  ",
-            "   ██  ",
-            "   ██  ",
-            "   ██  ",
-            "███████",
-        ],
-        '2' => vec![
-            "███████",
-            "      ██",
-            "      ██",
-            "███████",
-            "██     ",
-            "██     ",
-            "███████",
-        ],
-        '3' => vec![
-            "███████",
-            "      ██",
-            "      ██",
-            "███████",
-            "      ██",
-            "      ██",
-            "███████",
-        ],
-        '4' => vec![
-            "██   ██",
-            "██   ██",
-            "██   ██",
-            "███████",
-            "      ██",
-            "      ██",
-            "      ██",
-        ],
-        '5' => vec![
-            "███████",
-            "██     ",
-            "██     ",
-            "███████",
-            "      ██",
-            "      ██",
-            "███████",
-        ],
-        '6' => vec![
-            "███████",
-            "██     ",
-            "██     ",
-            "███████",
-            "██   ██",
-            "██   ██",
-            "███████",
-        ],
-        '7' => vec![
-            "███████",
-            "      ██",
-            "      ██",
-            "      ██",
-            "      ██",
-            "      ██",
-            "      ██",
-        ],
-        '8' => vec![
-            "███████",
-            "██   ██",
-            "██   ██",
-            "███████",
-            "██   ██",
-            "██   ██",
-            "███████",
-        ],
-        '9' => vec![
-            "███████",
-            "██   ██",
-            "██   ██",
-            "███████",
-            "      ██",
-            "      ██",
-            "███████",
-        ],
-        ':' => vec![
-            "       ",
-            "   ██  ",
-            "   ██  ",
-            "       ",
-            "   ██  ",
-            "   ██  ",
-            "       ",
-        ],
-        _ => vec![
-            "       ",
-            "       ",
-            "       ",
-            "       ",
-            "       ",
-            "       ",
-            "       ",
-        ],
-    }
-}
-
-fn format_time_ascii(time_str: &str) -> Vec<String> {
-    let mut lines = vec![String::new(); 7];
-    
-    for ch in time_str.chars() {
-        let digit_lines = get_ascii_digit(ch);
-        for (i, line) in digit_lines.iter().enumerate() {
-            if i < lines.len() {
-                lines[i].push_str(line);
-                lines[i].push(' ');
-            }
+/// The clock box, showing whichever face is picked.
+fn render_clock(f: &mut ratatui::Frame, app: &App, now: DateTime<Local>, area: Rect) {
+    let colors = app.colors();
+    let clock = &app.config.clock;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().fg(colors.accent));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let face: Box<dyn ClockFace + '_> = match app.settings.face {
+        Face::Digital => Box::new(Digital { clock, font: &app.font, seconds_font: app.seconds_font.as_ref(), colors }),
+        Face::Analog => Box::new(Analog {
+            clock,
+            colors,
+            alarm: app.alarms.next_due().and_then(|alarm| alarm.next_ring()),
+        }),
+        Face::Binary => Box::new(Binary { clock, colors }),
+        Face::Words => Box::new(Words { clock, colors }),
+        Face::Epoch => Box::new(Epoch { font: &app.font, colors }),
+    };
+    face.render(now, inner, f.buffer_mut());
+}
+
+/// What the animated background shows, taken from the latest weather.
+#[derive(Clone, Copy)]
+struct Scene {
+    precipitation: Precipitation,
+    /// How much is coming down, from 0.0 to 1.0.
+    intensity: f32,
+    stars: bool,
+    fog: bool,
+    lightning: bool,
+    /// Columns the wind carries rain and snow per row they fall; positive
+    /// to the right.
+    drift: f32,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Precipitation {
+    None,
+    Drizzle,
+    Rain,
+    Snow,
+}
+
+impl Scene {
+    /// Drizzle and a breeze until the weather is known.
+    const UNKNOWN: Scene = Scene {
+        precipitation: Precipitation::Drizzle,
+        intensity: 0.0,
+        stars: false,
+        fog: false,
+        lightning: false,
+        drift: 0.0,
+    };
+
+    fn new(weather: &Weather, units: Units) -> Scene {
+        let Some(report) = weather.report() else {
+            return Scene::UNKNOWN;
+        };
+        let current = &report.current;
+        let (precipitation, typical) = match current.sky {
+            Sky::Drizzle => (Precipitation::Drizzle, 0.0),
+            Sky::Rain => (Precipitation::Rain, 0.4),
+            Sky::Thunderstorm => (Precipitation::Rain, 0.8),
+            Sky::Snow => (Precipitation::Snow, 0.4),
+            Sky::Clear | Sky::Clouds | Sky::Fog => (Precipitation::None, 0.0),
+        };
+        // 8 mm an hour is heavy rain.
+        let intensity = if current.precipitation > 0.0 {
+            (current.precipitation as f32 / 8.0).clamp(0.1, 1.0)
+        } else {
+            typical
+        };
+        let metres_per_second = match units {
+            Units::Metric => current.wind_speed,
+            Units::Imperial => current.wind_speed * 0.447,
+        } as f32;
+        // The direction is where the wind comes from, so a westerly (270°)
+        // blows to the right.
+        let towards = -f32::from(current.wind_direction).to_radians().sin();
+        Scene {
+            precipitation,
+            intensity,
+            stars: current.sky == Sky::Clear && !current.is_day,
+            fog: current.sky == Sky::Fog,
+            lightning: current.sky == Sky::Thunderstorm,
+            drift: (towards * metres_per_second / 10.0).clamp(-1.5, 1.5),
         }
     }
-    
-    lines
+
+    /// Whether lightning lights up the sky on this frame.
+    fn flash(&self, frame: u32) -> bool {
+        // About one strike every ten seconds, often flickering twice.
+        self.lightning && (noise(frame as i32, 0).is_multiple_of(70) || noise(frame as i32 - 1, 0).is_multiple_of(70))
+    }
 }
 
-fn generate_animated_background(frame: u32, width: u16, height: u16) -> Vec<String> {
+/// Cheap, repeatable pseudo-random number for a cell.
+fn noise(x: i32, y: i32) -> u32 {
+    let mut h = (x as u32).wrapping_mul(0x9e37_79b1) ^ (y as u32).wrapping_mul(0x85eb_ca77);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2c1b_3c6d);
+    h ^ (h >> 12)
+}
+
+fn generate_animated_background(frame: u32, width: u16, height: u16, scene: &Scene) -> Vec<String> {
     let mut background = Vec::new();
-    
+    let bolt = noise(frame as i32 / 2, 1) as i32 % i32::from(width.max(1));
+
     for y in 0..height {
         let mut line = String::new();
         for x in 0..width {
-            let char_to_add = if y == height - 3 && x >= 2 && x <= 8 {
+            let char_to_add = if y == height - 3 && (2..=8).contains(&x) {
                 // Street lamp pole
                 if x == 5 {
                     '│'
                 } else {
                     ' '
                 }
-            } else if y == height - 4 && x >= 3 && x <= 7 {
+            } else if y == height - 4 && (3..=7).contains(&x) {
                 // Street lamp light (animated glow)
                 let glow_intensity = (frame as f32 * 0.1).sin() * 0.5 + 0.5;
                 if glow_intensity > 0.3 {
@@ -235,24 +800,13 @@ fn generate_animated_background(frame: u32, width: u16, height: u16) -> Vec<Stri
                     } else {
                         '·'
                     }
-                } else {
-                    if x == 5 {
-                        '○'
-                    } else {
-                        ' '
-                    }
-                }
-            } else if y < height - 5 {
-                // Rain/wind effect
-                let wind_offset = ((frame as f32 * 0.05).sin() * 2.0) as i32;
-                let rain_pos = (x as i32 + y as i32 + wind_offset + (frame / 3) as i32) % 7;
-                if rain_pos == 0 && (frame + x as u32) % 13 == 0 {
-                    '·'
-                } else if rain_pos == 1 && (frame + x as u32) % 17 == 0 {
-                    '`'
+                } else if x == 5 {
+                    '○'
                 } else {
                     ' '
                 }
+            } else if y < height - 5 {
+                sky_cell(frame, x as i32, y as i32, bolt, scene)
             } else {
                 ' '
             };
@@ -260,21 +814,132 @@ fn generate_animated_background(frame: u32, width: u16, height: u16) -> Vec<Stri
         }
         background.push(line);
     }
-    
+
     background
 }
 
+/// The sky above the street lamp.
+fn sky_cell(frame: u32, x: i32, y: i32, bolt: i32, scene: &Scene) -> char {
+    let frame_i = frame as i32;
+
+    if scene.flash(frame) {
+        // Zigzag down from the top of the screen.
+        let zag = (y / 2) % 3 - 1;
+        if x == bolt + zag {
+            return if zag < 0 { '╱' } else { '╲' };
+        }
+    }
+
+    if scene.fog {
+        // Banks drifting slowly across every fourth pair of rows.
+        let shift = (frame_i as f32 * (0.1 + scene.drift.abs() * 0.2)) as i32 * scene.drift.signum() as i32;
+        let bank = ((x - shift) as f32 * 0.12 + y as f32 * 0.7).sin();
+        if y % 4 < 2 && bank > 0.2 {
+            return if bank > 0.7 { '▒' } else { '░' };
+        }
+    }
+
+    if scene.stars {
+        let star = noise(x, y);
+        if star.is_multiple_of(60) {
+            // Twinkle, each star at its own pace.
+            return match (frame / 4 + star / 60) % 9 {
+                0 => '+',
+                1 => ' ',
+                _ => '·',
+            };
+        }
+    }
+
+    // The wind leans the fall and adds gusts on top of a gentle sway.
+    let wind_offset = ((frame as f32 * 0.05).sin() * (2.0 + scene.drift.abs() * 2.0)) as i32;
+    match scene.precipitation {
+        Precipitation::None => ' ',
+        Precipitation::Drizzle => {
+            let lean = (scene.drift * (y + frame_i / 3) as f32) as i32;
+            let rain_pos = (x - lean + y + wind_offset + frame_i / 3).rem_euclid(7);
+            if rain_pos == 0 && (frame + x as u32).is_multiple_of(13) {
+                '·'
+            } else if rain_pos == 1 && (frame + x as u32).is_multiple_of(17) {
+                '`'
+            } else {
+                ' '
+            }
+        }
+        Precipitation::Rain => {
+            let fall = y - frame_i;
+            let lean = (scene.drift * fall as f32).round() as i32;
+            let density = 20 + (scene.intensity * 140.0) as u32;
+            if noise(x + lean + wind_offset, fall) % 1000 >= density {
+                ' '
+            } else if scene.drift > 0.3 {
+                '\\'
+            } else if scene.drift < -0.3 {
+                '/'
+            } else if scene.intensity < 0.3 {
+                '╎'
+            } else {
+                '│'
+            }
+        }
+        Precipitation::Snow => {
+            // Flakes fall a row every third frame and wander sideways.
+            let fall = y - frame_i / 3;
+            let lean = (scene.drift * fall as f32).round() as i32;
+            let sway = ((frame as f32 * 0.1 + fall as f32 * 0.5).sin() * 1.2).round() as i32;
+            let density = 15 + (scene.intensity * 80.0) as u32;
+            let flake = noise(x + lean + sway, fall);
+            if flake % 1000 >= density {
+                ' '
+            } else if flake.is_multiple_of(3) {
+                '*'
+            } else {
+                '·'
+            }
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let _cli = Cli::parse();
-    
+    let cli = Cli::parse();
+
+    // Load before touching the terminal so a broken config or state file is
+    // reported on a usable screen.
+    let mut config = Config::load(cli.config.as_deref())?;
+    if let Some(path) = cli.pcm_out {
+        config.audio.output = Output::File;
+        config.audio.file = Some(path);
+    }
+    let store = Store::open_default()?;
+    if let Some(path) = &cli.import_stations {
+        let mut state = store.load()?;
+        let dropped = state.stations.import(path)?;
+        store.save(&state.settings, &state.alarms, &state.stations)?;
+        println!("Imported {} stations", state.stations.len());
+        if dropped > 0 {
+            println!("Skipped {dropped} stations past the {MAX_PRESETS} preset keys");
+        }
+        for (slot, reason) in state.stations.unplayable() {
+            let url = state.stations.get(slot).map_or("", |station| station.url.as_str());
+            println!("Warning: preset {} ({url}) will not play: {reason}", radio::key_for_slot(slot));
+        }
+        return Ok(());
+    }
+    if let Some(path) = &cli.export_stations {
+        let state = store.load()?;
+        state.stations.export(path)?;
+        println!("Exported {} stations to {}", state.stations.len(), path.display());
+        return Ok(());
+    }
+    let mut app = App::new(config, store, cli.colors.unwrap_or_else(ColorMode::detect))?;
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
     let res = run_app(&mut terminal, &mut app).await;
 
     disable_raw_mode()?;
@@ -288,54 +953,84 @@ async fn main() -> Result<()> {
     if let Err(err) = res {
         println!("{err:?}");
     }
+    if let Some(status) = &app.status {
+        eprintln!("{status}");
+    }
 
     Ok(())
 }
 
 async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<()> {
-    
-    loop {
-        terminal.draw(|f| ui(f, app))?;
+    let mut last_bell = 0;
 
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    app.handle_key_event(key.code);
-                }
+    loop {
+        terminal.draw(|f| {
+            ui(f, app);
+            if app.color_mode == ColorMode::Mono {
+                color::monochrome(f.buffer_mut(), app.colors());
             }
+        })?;
+
+        if event::poll(app.config.animation.frame_interval())?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key_event(key.code);
         }
 
         if app.should_quit {
+            app.save();
             return Ok(());
         }
 
+        let now = Local::now();
+        app.check_alarms(now);
+        app.check_sleep(now);
+        if !app.ringing.is_empty() && app.player.is_none() && now.timestamp() != last_bell {
+            last_bell = now.timestamp();
+            // Without a sound player, ring the terminal bell once a second.
+            let mut stdout = io::stdout();
+            stdout.write_all(b"\x07")?;
+            stdout.flush()?;
+        }
 
-        if let Some(alarm_time) = app.alarm_time {
-            if Local::now() >= alarm_time {
-                app.alarm_time = None;
-            }
+        if app.dirty {
+            app.save();
         }
 
-        app.animation_frame = app.animation_frame.wrapping_add(1);
-        sleep(Duration::from_millis(50)).await;
+        if app.config.animation.enabled {
+            app.animation_frame = app.animation_frame.wrapping_add(1);
+        }
+        // Let background tasks run between frames.
+        tokio::task::yield_now().await;
     }
 }
 
 fn ui(f: &mut ratatui::Frame, app: &App) {
     let size = f.size();
-    
+    let colors = app.colors();
+
     // Render animated background
-    let background_lines = generate_animated_background(app.animation_frame, size.width, size.height);
     let mut bg_spans = Vec::new();
-    for line in background_lines {
-        bg_spans.push(Line::from(vec![Span::styled(
-            line,
-            Style::default().fg(Color::Rgb(100, 100, 100)),
-        )]));
+    if app.config.animation.enabled {
+        let scene = Scene::new(&app.weather.borrow(), app.config.weather.units);
+        // Lightning lights the scenery up for a frame.
+        let scenery = if scene.flash(app.animation_frame) {
+            colors.text
+        } else {
+            colors.dim
+        };
+        let background_lines = generate_animated_background(app.animation_frame, size.width, size.height, &scene);
+        for line in background_lines {
+            bg_spans.push(Line::from(vec![Span::styled(
+                line,
+                Style::default().fg(scenery),
+            )]));
+        }
     }
-    
+
     let background = Paragraph::new(bg_spans)
-        .style(Style::default().bg(Color::Black));
+        .style(Style::default().bg(colors.background));
     f.render_widget(background, size);
     
     let main_layout = Layout::default()
@@ -347,80 +1042,783 @@ fn ui(f: &mut ratatui::Frame, app: &App) {
         ])
         .split(size);
 
-    let header = Paragraph::new("'a' alarm | 'q' quit")
+    let mut hints = "'a' alarm | 'l' alarms | 'r' radio | 's' sleep".to_string();
+    if !matches!(*app.weather.borrow(), Weather::Disabled) {
+        hints.push_str(" | 'f' forecast");
+    }
+    hints.push_str(&format!(" | 'c' {} | 't' theme", app.settings.face.next().name()));
+    if !app.stations.is_empty() {
+        match app.stations.len() {
+            1 => hints.push_str(" | '1' preset"),
+            n => hints.push_str(&format!(" | '1'-'{}' presets", radio::key_for_slot(n - 1))),
+        }
+    }
+    hints.push_str(" | 'q' quit");
+    let header = Paragraph::new(hints)
         .alignment(Alignment::Center)
-        .style(Style::default().fg(Color::Rgb(255, 107, 138)));
+        .style(Style::default().fg(colors.accent));
 
     f.render_widget(header, main_layout[0]);
 
-    let now = Local::now();
-    let time_str = now.format("%H:%M").to_string();
-    let date_str = now.format("%A, %B %d, %Y").to_string();
-
-    let ascii_lines = format_time_ascii(&time_str);
-    let mut clock_lines = Vec::new();
-    
-    for line in ascii_lines {
-        clock_lines.push(Line::from(vec![Span::styled(
-            line,
-            Style::default()
-                .fg(Color::Rgb(255, 107, 138))
-                .add_modifier(Modifier::BOLD),
-        )]));
-    }
-    
-    clock_lines.push(Line::from(vec![Span::styled(
-        "",
-        Style::default().fg(Color::White),
-    )]));
-    
-    clock_lines.push(Line::from(vec![Span::styled(
-        date_str,
-        Style::default().fg(Color::White),
-    )]));
-
-    let clock_block = Block::default()
-        .borders(Borders::ALL)
-        .style(Style::default().fg(Color::Rgb(255, 107, 138)));
+    let weather = app.weather.borrow().clone();
+    let main_area = match weather {
+        // The side panel needs room next to the big digits.
+        Weather::Disabled => main_layout[1],
+        _ if size.width < 80 => main_layout[1],
+        weather => {
+            let areas = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(1), Constraint::Length(30)])
+                .split(main_layout[1]);
+            let time_format = app.config.clock.short_time_format();
+            render_weather(f, &weather, app.config.weather.units, time_format, colors, areas[1]);
+            areas[0]
+        }
+    };
 
-    let clock = Paragraph::new(clock_lines)
-        .block(clock_block)
-        .alignment(Alignment::Center);
+    let clock_area = match &app.radio {
+        Some(radio) => {
+            let areas = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(1), Constraint::Length(4)])
+                .split(main_area);
+            let info = radio.info();
+            let slot = app.stations.slot_of(&info.url);
+            render_radio(f, &info, slot, colors, areas[1]);
+            areas[0]
+        }
+        None => main_area,
+    };
 
-    f.render_widget(clock, main_layout[1]);
+    let now = Local::now();
+    render_clock(f, app, now, clock_area);
 
     let bottom_layout = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(100)])
         .split(main_layout[2]);
 
-    let alarm_text = if let Some(alarm_time) = app.alarm_time {
-        format!("Alarm: {}", alarm_time.format("%H:%M"))
-    } else {
-        "No alarm set".to_string()
+    let alarm_text = match app.alarms.next_due() {
+        Some(next) => {
+            let at = next.next_ring().unwrap_or_default();
+            let mut text = format!("Alarm: {}", at.format(app.config.clock.short_time_format()));
+            if !next.label.is_empty() {
+                text.push_str(&format!(" {}", next.label));
+            }
+            let others = app.alarms.pending() - 1;
+            if others > 0 {
+                text.push_str(&format!(" (+{others} pending)"));
+            }
+            text
+        }
+        None => "No alarm set".to_string(),
+    };
+    let alarm_text = match app.sleep {
+        Some((_, until)) => {
+            let left = (until - now).num_seconds().max(0);
+            format!("{alarm_text} | Sleep {}:{:02}", left / 60, left % 60)
+        }
+        None => alarm_text,
     };
 
-    let alarm_widget = Paragraph::new(alarm_text)
+    let mut bottom_lines = vec![Line::from(alarm_text)];
+    if let Some(status) = &app.status {
+        bottom_lines.push(Line::from(Span::styled(status.clone(), Style::default().fg(colors.alert))));
+    }
+
+    let alarm_widget = Paragraph::new(bottom_lines)
         .alignment(Alignment::Center)
-        .style(Style::default().fg(Color::White));
+        .style(Style::default().fg(colors.text));
 
     f.render_widget(alarm_widget, bottom_layout[0]);
 
-    if app.show_alarm_dialog {
-        let popup_area = centered_rect(40, 20, size);
-        f.render_widget(Clear, popup_area);
-        
-        let popup_block = Block::default()
-            .title("Set Alarm (HH:MM)")
-            .borders(Borders::ALL)
-            .style(Style::default().bg(Color::Black).fg(Color::Rgb(255, 107, 138)));
+    if let Some(ringing) = app.ringing.first() {
+        render_ringing(f, app, ringing, size);
+        return;
+    }
 
-        let popup_text = Paragraph::new(app.alarm_input.as_str())
-            .block(popup_block)
-            .style(Style::default().bg(Color::Black).fg(Color::White));
+    match app.mode {
+        Mode::Clock => {}
+        Mode::AlarmList => render_alarm_list(f, app, size),
+        Mode::EditAlarm => {
+            if app.editor.back == Mode::AlarmList {
+                render_alarm_list(f, app, size);
+            }
+            render_alarm_editor(f, &app.editor, colors, size);
+        }
+        Mode::SleepTimer => render_sleep_prompt(f, &app.sleep_input, colors, size),
+        Mode::Forecast => render_forecast(f, app, main_layout[1]),
+    }
+}
+
+fn render_radio(
+    f: &mut ratatui::Frame,
+    info: &StreamInfo,
+    slot: Option<usize>,
+    colors: &Theme,
+    area: Rect,
+) {
+    let station = info.station.clone().unwrap_or_else(|| info.url.clone());
+    let (state, color) = match &info.state {
+        StreamState::Connecting => ("connecting".to_string(), colors.dim),
+        StreamState::Buffering(percent) => (format!("buffering {percent}%"), colors.dim),
+        StreamState::Playing => ("playing".to_string(), colors.accent),
+        StreamState::Reconnecting { attempt, error } => {
+            (format!("reconnecting ({attempt}): {error}"), colors.alert)
+        }
+        StreamState::Failed(error) => (format!("failed: {error}"), colors.alert),
+    };
+    let lines = vec![
+        Line::from(vec![
+            Span::styled(station, Style::default().fg(colors.text).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("  {state}"), Style::default().fg(color)),
+        ]),
+        Line::from(Span::styled(
+            info.title.clone().unwrap_or_default(),
+            Style::default().fg(colors.text),
+        )),
+    ];
+    let panel = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(match slot {
+                    Some(slot) => format!(" Radio {} ", radio::key_for_slot(slot)),
+                    None => " Radio ".to_string(),
+                })
+                .style(Style::default().fg(colors.accent)),
+        )
+        .alignment(Alignment::Center);
+    f.render_widget(panel, area);
+}
 
-        f.render_widget(popup_text, popup_area);
+fn render_weather(
+    f: &mut ratatui::Frame,
+    weather: &Weather,
+    units: Units,
+    time_format: &str,
+    colors: &Theme,
+    area: Rect,
+) {
+    let mut lines = match weather.report() {
+        Some(report) => weather_lines(report, units, time_format, colors),
+        None if matches!(weather, Weather::Loading) => {
+            vec![Line::from(Span::styled("Loading...", Style::default().fg(colors.dim)))]
+        }
+        None => Vec::new(),
+    };
+    let status = weather_status(weather, time_format, colors);
+    if !status.is_empty() && !lines.is_empty() {
+        lines.push(Line::from(""));
+    }
+    lines.extend(status);
+    let title = match weather {
+        Weather::Ready { report, .. } if !report.current.place.is_empty() => format!(" {} ", report.current.place),
+        Weather::Stale { report, .. } if !report.current.place.is_empty() => {
+            format!(" {} (stale) ", report.current.place)
+        }
+        Weather::Stale { .. } => " Weather (stale) ".to_string(),
+        _ => " Weather ".to_string(),
+    };
+    let panel = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .style(Style::default().fg(colors.accent)),
+        )
+        .wrap(Wrap { trim: true });
+    f.render_widget(panel, area);
+}
+
+/// Why the weather is out of date or missing, and when it is next fetched.
+fn weather_status(weather: &Weather, time_format: &str, colors: &Theme) -> Vec<Line<'static>> {
+    let alert = Style::default().fg(colors.alert);
+    let dim = Style::default().fg(colors.dim);
+    let (fetched, failure) = match weather {
+        Weather::Ready { cache_error: Some(error), .. } => {
+            return vec![Line::from(Span::styled(format!("Not cached: {error}"), alert))];
+        }
+        Weather::Stale { fetched, failure, .. } => (Some(fetched), failure.as_ref()),
+        Weather::Failed(failure) => (None, Some(failure)),
+        _ => return Vec::new(),
+    };
+    let mut lines = Vec::new();
+    if let Some(fetched) = fetched {
+        let format = if fetched.date_naive() == Local::now().date_naive() {
+            time_format.to_string()
+        } else {
+            format!("%a {time_format}")
+        };
+        let (state, style) = match failure {
+            None => ("Updating", dim),
+            Some(_) => ("Stale", alert),
+        };
+        lines.push(Line::from(Span::styled(
+            format!("{state}, from {}", fetched.format(&format)),
+            style,
+        )));
+    }
+    if let Some(failure) = failure {
+        lines.push(Line::from(Span::styled(failure.message.clone(), alert)));
+        if let Some(retry_at) = failure.retry_at {
+            lines.push(Line::from(Span::styled(
+                format!("Retrying at {}", retry_at.format(time_format)),
+                dim,
+            )));
+        }
     }
+    lines
+}
+
+fn weather_lines(report: &Report, units: Units, time_format: &str, colors: &Theme) -> Vec<Line<'static>> {
+    let conditions = &report.current;
+    let text = Style::default().fg(colors.text);
+    let dim = Style::default().fg(colors.dim);
+    let mut summary = conditions.summary.clone();
+    if let Some(first) = summary.get_mut(..1) {
+        first.make_ascii_uppercase();
+    }
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("{:.0}{}", conditions.temperature, units.temperature()),
+            text.add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(summary, text)),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("Feels like {:.0}{}", conditions.feels_like, units.temperature()),
+            text,
+        )),
+        Line::from(Span::styled(format!("Humidity {}%", conditions.humidity), text)),
+        Line::from(Span::styled(
+            format!(
+                "Wind {:.1} {} {}",
+                conditions.wind_speed,
+                units.speed(),
+                weather::compass(conditions.wind_direction)
+            ),
+            text,
+        )),
+    ];
+    if let Some(today) = report.daily.first() {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "Low {:.0}{} High {:.0}{}",
+                today.min,
+                units.temperature(),
+                today.max,
+                units.temperature()
+            ),
+            text,
+        )));
+        lines.push(Line::from(Span::styled(
+            format!("Rain {}%", today.precipitation_chance),
+            text,
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!("Updated {}", conditions.observed.format(time_format)),
+        dim,
+    )));
+    lines
+}
+
+fn render_forecast(f: &mut ratatui::Frame, app: &App, area: Rect) {
+    let colors = app.colors();
+    let units = app.config.weather.units;
+    f.render_widget(Clear, area);
+    let weather = app.weather.borrow().clone();
+    let title = match weather.report() {
+        Some(report) if !report.current.place.is_empty() => {
+            format!(" Forecast for {} ", report.current.place)
+        }
+        _ => " Forecast ".to_string(),
+    };
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_bottom(" 'f' or Esc back ")
+        .style(Style::default().bg(colors.background).fg(colors.accent));
+    let status = weather_status(&weather, app.config.clock.short_time_format(), colors);
+
+    let Some(report) = weather.report() else {
+        let status = match weather {
+            Weather::Disabled => vec![Line::styled("Weather is turned off", Style::default().fg(colors.dim))],
+            Weather::Loading => vec![Line::styled("Loading...", Style::default().fg(colors.dim))],
+            _ => status,
+        };
+        let paragraph = Paragraph::new(status)
+            .block(block)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        f.render_widget(paragraph, area);
+        return;
+    };
+    // Keep the reason for stale data in sight, in the top right corner.
+    if !status.is_empty() {
+        let mut spans = vec![Span::raw(" ")];
+        for (i, line) in status.into_iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::raw(" · "));
+            }
+            spans.extend(line.spans);
+        }
+        spans.push(Span::raw(" "));
+        block = block.title(Title::from(Line::from(spans)).alignment(Alignment::Right));
+    }
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(9),
+            Constraint::Length(1),
+            Constraint::Length(8),
+            Constraint::Min(0),
+        ])
+        .split(inner);
+
+    let text = Style::default().fg(colors.text);
+    let days: Vec<_> = report.daily.iter().take(7).collect();
+    if days.is_empty() {
+        f.render_widget(
+            Paragraph::new(Span::styled("No daily forecast", Style::default().fg(colors.dim)))
+                .alignment(Alignment::Center),
+            areas[0],
+        );
+    } else {
+        let today = Local::now().date_naive();
+        let header = Row::new(days.iter().map(|day| {
+            let name = if day.date == today {
+                "Today".to_string()
+            } else {
+                day.date.format("%a %d").to_string()
+            };
+            Cell::from(Line::from(name).alignment(Alignment::Center))
+        }))
+        .style(text.add_modifier(Modifier::BOLD));
+        let centered = |line: String, style: Style| Cell::from(Line::styled(line, style).alignment(Alignment::Center));
+        let icons = Row::new(days.iter().map(|day| {
+            let lines: Vec<Line> = weather_icon(day.sky)
+                .iter()
+                .map(|line| Line::from(*line).alignment(Alignment::Center))
+                .collect();
+            Cell::from(lines).style(Style::default().fg(colors.accent))
+        }))
+        .height(4);
+        let skies = Row::new(days.iter().map(|day| centered(sky_name(day.sky).to_string(), text)));
+        let temperatures = Row::new(days.iter().map(|day| {
+            centered(
+                format!("{:.0}° / {:.0}°", day.max, day.min),
+                text.add_modifier(Modifier::BOLD),
+            )
+        }));
+        let rain = Row::new(days.iter().map(|day| {
+            let style = if day.precipitation_chance >= 50 {
+                text
+            } else {
+                Style::default().fg(colors.dim)
+            };
+            centered(format!("{}% rain", day.precipitation_chance), style)
+        }));
+        let table = Table::new(
+            [icons, skies, temperatures, rain],
+            days.iter().map(|_| Constraint::Ratio(1, days.len() as u32)),
+        )
+        .header(header.bottom_margin(1));
+        f.render_widget(table, areas[0]);
+    }
+
+    render_temperature_sparkline(f, report, units, app.config.clock.short_time_format(), colors, areas[2]);
+}
+
+/// Temperature over the next 24 hours, spread across the full width.
+fn render_temperature_sparkline(
+    f: &mut ratatui::Frame,
+    report: &Report,
+    units: Units,
+    time_format: &str,
+    colors: &Theme,
+    area: Rect,
+) {
+    let now = Local::now();
+    let end = now + TimeDelta::hours(24);
+    let hours: Vec<(f64, f64)> = report
+        .hourly
+        .iter()
+        .filter(|hour| hour.time > now - TimeDelta::hours(3) && hour.time < end + TimeDelta::hours(3))
+        .map(|hour| ((hour.time - now).num_seconds() as f64 / 3600.0, hour.temperature))
+        .collect();
+    let block = Block::default()
+        .borders(Borders::TOP)
+        .style(Style::default().fg(colors.accent));
+    if hours.len() < 2 {
+        let empty = Paragraph::new(Span::styled("No hourly forecast", Style::default().fg(colors.dim)))
+            .block(block.title(" Next 24 hours "))
+            .alignment(Alignment::Center);
+        f.render_widget(empty, area);
+        return;
+    }
+
+    // Sample the forecast, which may only come every three hours, once per
+    // column.
+    let columns = area.width.max(2) as usize;
+    let samples: Vec<f64> = (0..columns)
+        .map(|column| {
+            let at = 24.0 * column as f64 / (columns - 1) as f64;
+            let after = hours.iter().position(|&(hour, _)| hour >= at).unwrap_or(hours.len() - 1).max(1);
+            let (h0, t0) = hours[after - 1];
+            let (h1, t1) = hours[after];
+            let weight = ((at - h0) / (h1 - h0)).clamp(0.0, 1.0);
+            t0 + (t1 - t0) * weight
+        })
+        .collect();
+    let low = samples.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = samples.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    // Sparkline bars start at zero; lift the coldest hour to a sliver.
+    let data: Vec<u64> = samples
+        .iter()
+        .map(|t| ((t - low) * 10.0).round() as u64 + 1)
+        .collect();
+    let title = format!(
+        " Next 24 hours  {low:.0}{unit} to {high:.0}{unit} ",
+        unit = units.temperature()
+    );
+
+    let areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(area);
+    let sparkline = Sparkline::default()
+        .block(block.title(title))
+        .data(&data)
+        .max(((high - low) * 10.0).round() as u64 + 1)
+        .style(Style::default().fg(colors.accent));
+    f.render_widget(sparkline, areas[0]);
+
+    let axis = time_axis(
+        areas[1].width as usize,
+        &(now + TimeDelta::hours(12)).format(time_format).to_string(),
+        &end.format(time_format).to_string(),
+    );
+    f.render_widget(
+        Paragraph::new(Span::styled(axis, Style::default().fg(colors.dim))),
+        areas[1],
+    );
+}
+
+/// `now`, the middle time and the end time spread across `width` columns.
+fn time_axis(width: usize, middle: &str, end: &str) -> String {
+    let mut axis = format!("{:<width$}", "now");
+    let centre = (width / 2).saturating_sub(middle.len() / 2);
+    if centre > 4 && centre + middle.len() + end.len() < width {
+        axis.replace_range(centre..centre + middle.len(), middle);
+    }
+    if end.len() + 4 <= width {
+        axis.replace_range(width - end.len()..width, end);
+    }
+    axis
+}
+
+/// Four lines of ASCII art for a day's weather.
+fn weather_icon(sky: Sky) -> [&'static str; 4] {
+    const CLOUD: [&str; 3] = ["    .--.   ", " .-(    ). ", "(___.__)__)"];
+    match sky {
+        Sky::Clear => [r"   \   /   ", r"    .-.    ", r" - (   ) - ", r"    `-'    "],
+        Sky::Clouds => ["           ", CLOUD[0], CLOUD[1], CLOUD[2]],
+        Sky::Fog => ["           ", " _ - _ - _ ", "  _ - _ -  ", " _ - _ - _ "],
+        Sky::Drizzle => [CLOUD[0], CLOUD[1], CLOUD[2], "  ' ' ' '  "],
+        Sky::Rain => [CLOUD[0], CLOUD[1], CLOUD[2], "  / / / /  "],
+        Sky::Snow => [CLOUD[0], CLOUD[1], CLOUD[2], "  * * * *  "],
+        Sky::Thunderstorm => [CLOUD[0], CLOUD[1], CLOUD[2], "  /_ /_ /_ "],
+    }
+}
+
+fn sky_name(sky: Sky) -> &'static str {
+    match sky {
+        Sky::Clear => "Clear",
+        Sky::Clouds => "Cloudy",
+        Sky::Fog => "Fog",
+        Sky::Drizzle => "Drizzle",
+        Sky::Rain => "Rain",
+        Sky::Snow => "Snow",
+        Sky::Thunderstorm => "Storms",
+    }
+}
+
+fn render_alarm_list(f: &mut ratatui::Frame, app: &App, size: Rect) {
+    let colors = app.colors();
+    let time_format = app.config.clock.short_time_format();
+    let area = centered_rect(60, 60, size);
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title("Alarms")
+        .title_bottom(format!(
+            "'a' add | Enter edit | Space toggle | 's' skip next | 'd' delete | '+'/'-' snooze {} min | Esc close",
+            app.settings.snooze_minutes
+        ))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(colors.background).fg(colors.accent));
+
+    if app.alarms.is_empty() {
+        let empty = Paragraph::new("No alarms yet, press 'a' to add one")
+            .block(block)
+            .alignment(Alignment::Center)
+            .style(Style::default().bg(colors.background).fg(colors.text));
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .alarms
+        .iter()
+        .map(|alarm| {
+            let mut status = match (alarm.due, &alarm.adjustment) {
+                (Some(due), Some(adjustment)) => match adjustment.action {
+                    Action::Earlier(minutes) => {
+                        let at = alarm.adjusted_due().unwrap_or(due);
+                        format!(
+                            "next {} {} ({minutes}m early, {})",
+                            at.format("%a %d"),
+                            at.format(time_format),
+                            adjustment.reason
+                        )
+                    }
+                    Action::Skip => format!(
+                        "skipping {} {} ({})",
+                        due.format("%a %d"),
+                        due.format(time_format),
+                        adjustment.reason
+                    ),
+                },
+                (Some(due), None) => format!("next {} {}", due.format("%a %d"), due.format(time_format)),
+                (None, _) => "off".to_string(),
+            };
+            if let Some(rule) = &alarm.rule
+                && alarm.adjustment.is_none()
+            {
+                status.push_str(&format!(", {rule}"));
+            }
+            if let Some(skip) = alarm.skip {
+                status.push_str(&format!(" (skipping {})", skip.format("%a %d")));
+            }
+            if let Some(snooze) = alarm.snoozed_until {
+                status.push_str(&format!(", snoozed until {}", snooze.format(time_format)));
+            }
+            if let Some(ring) = alarm.last_ring() {
+                status.push_str(&format!(
+                    ", {} {} {}",
+                    ring.outcome.as_str(),
+                    ring.at.format("%a"),
+                    ring.at.format(time_format)
+                ));
+            }
+            let style = if alarm.enabled {
+                Style::default().fg(colors.text)
+            } else {
+                Style::default().fg(colors.dim)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(if alarm.enabled { "[x] " } else { "[ ] " }, style),
+                Span::styled(
+                    alarm.time.format(time_format).to_string(),
+                    style.add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!(
+                        "  {:<16} {:<14} {:<10} {status}",
+                        alarm.label,
+                        alarm.schedule.to_string(),
+                        sound_name(&alarm.sound, &app.stations)
+                    ),
+                    style,
+                ),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(colors.accent).fg(colors.background))
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default().with_selected(Some(app.selected));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// Short name of a sound for the alarm list.
+fn sound_name(sound: &Sound, stations: &Presets) -> String {
+    match sound {
+        Sound::Tone => "tone".to_string(),
+        Sound::File(path) | Sound::Playlist(path) => path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string()),
+        Sound::Preset(slot) => match stations.get(*slot) {
+            Some(station) if !station.name.is_empty() => format!("{sound}: {}", station.name),
+            Some(_) => sound.to_string(),
+            None => format!("{sound} (unset)"),
+        },
+    }
+}
+
+/// What the ringing alarm is playing, for the overlay.
+fn playing_text(app: &App, sound: &Sound) -> String {
+    let name = sound_name(sound, &app.stations);
+    match app.player.as_ref().map(|(_, playing)| playing) {
+        Some(AlarmSound::Station { radio, .. }) => {
+            let info = radio.info();
+            match (&info.state, info.title) {
+                (StreamState::Playing, Some(title)) => format!("{name} - {title}"),
+                (StreamState::Playing, None) => name,
+                _ => format!("{name}, tuning in"),
+            }
+        }
+        Some(AlarmSound::Fallback { .. }) => format!("{name} did not play, ringing the tone"),
+        Some(AlarmSound::Player { .. }) => name,
+        None => "terminal bell".to_string(),
+    }
+}
+
+fn render_ringing(f: &mut ratatui::Frame, app: &App, ringing: &Ringing, size: Rect) {
+    let colors = app.colors();
+    // Swap foreground and background every half second.
+    let flash = Local::now().timestamp_subsec_millis() < 500;
+    let (fg, bg) = if flash {
+        (colors.background, colors.accent)
+    } else {
+        (colors.accent, colors.background)
+    };
+    let style = Style::default().fg(fg).bg(bg);
+
+    let alarm = app.alarms.get(ringing.alarm_id);
+    let label = match alarm {
+        Some(alarm) if !alarm.label.is_empty() => alarm.label.clone(),
+        _ => "Alarm".to_string(),
+    };
+    let sound = alarm.map(|alarm| alarm.sound.clone()).unwrap_or_default();
+
+    let time_lines = face::big_clock(
+        Local::now(),
+        &app.config.clock,
+        &app.font,
+        app.seconds_font.as_ref(),
+        size.width,
+        size.height.saturating_sub(7),
+    );
+    let top_padding = size.height.saturating_sub(time_lines.len() as u16 + 7) / 2;
+    let mut lines = vec![Line::from(""); top_padding as usize];
+    lines.extend(
+        time_lines
+            .into_iter()
+            .map(|line| Line::from(Span::styled(line, style.add_modifier(Modifier::BOLD)))),
+    );
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(label, style.add_modifier(Modifier::BOLD))));
+    lines.push(Line::from(Span::styled(format!("♪ {}", playing_text(app, &sound)), style)));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!(
+            "'d' dismiss | 'z' snooze {} min",
+            app.settings.snooze_minutes
+        ),
+        style,
+    )));
+    if app.ringing.len() > 1 {
+        lines.push(Line::from(Span::styled(
+            format!("{} more ringing", app.ringing.len() - 1),
+            style,
+        )));
+    }
+
+    f.render_widget(Clear, size);
+    f.render_widget(
+        Paragraph::new(lines).alignment(Alignment::Center).style(style),
+        size,
+    );
+}
+
+fn render_alarm_editor(f: &mut ratatui::Frame, editor: &AlarmEditor, colors: &Theme, size: Rect) {
+    let popup_area = centered_rect(50, 35, size);
+    f.render_widget(Clear, popup_area);
+
+    let title = if editor.id.is_some() { "Edit Alarm" } else { "Set Alarm" };
+    let popup_block = Block::default()
+        .title(title)
+        .title_bottom("Tab next field | Enter save | Esc cancel")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(colors.background).fg(colors.accent));
+
+    let field = |name: &str, value: &str, active: bool| {
+        let marker = if active { "> " } else { "  " };
+        let mut style = Style::default().fg(colors.text);
+        if active {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        Line::from(vec![
+            Span::styled(format!("{marker}{name:<7}"), Style::default().fg(colors.accent)),
+            Span::styled(value.to_string(), style),
+        ])
+    };
+
+    let mut lines = vec![
+        field("Time", &editor.time, editor.field == EditorField::Time),
+        field("Label", &editor.label, editor.field == EditorField::Label),
+        field("Repeat", &editor.repeat, editor.field == EditorField::Repeat),
+        field("Sound", &editor.sound, editor.field == EditorField::Sound),
+        field("Volume", &editor.volume, editor.field == EditorField::Volume),
+        field("Weather", &editor.weather, editor.field == EditorField::Weather),
+    ];
+    let hint = match editor.field {
+        EditorField::Time => Some("HH:MM, or 12-hour as in 7:30am"),
+        EditorField::Repeat => Some("once, daily, weekdays, weekends, mon,wed,fri or every N days"),
+        EditorField::Sound => Some("tone, preset 1-0, or a sound file or M3U/PLS playlist"),
+        EditorField::Volume => Some("e.g. 80% over 2m, curve linear, exp or stepped"),
+        EditorField::Weather => Some("e.g. 20m earlier if snow or freezing, skip if rain"),
+        _ => None,
+    };
+    if let Some(hint) = hint {
+        lines.push(Line::from(Span::styled(hint, Style::default().fg(colors.dim))));
+    }
+    if let Some(error) = &editor.error {
+        lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(colors.alert))));
+    }
+
+    let popup_text = Paragraph::new(lines)
+        .block(popup_block)
+        .style(Style::default().bg(colors.background).fg(colors.text));
+
+    f.render_widget(popup_text, popup_area);
+}
+
+fn render_sleep_prompt(f: &mut ratatui::Frame, input: &str, colors: &Theme, size: Rect) {
+    let popup_area = centered_rect(40, 20, size);
+    f.render_widget(Clear, popup_area);
+
+    let popup_block = Block::default()
+        .title("Sleep Timer")
+        .title_bottom("Enter start | Esc cancel")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(colors.background).fg(colors.accent));
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("> Minutes ", Style::default().fg(colors.accent)),
+            Span::styled(
+                input.to_string(),
+                Style::default().fg(colors.text).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(Span::styled("1 to 720, empty or 0 turns it off", Style::default().fg(colors.dim))),
+    ];
+    let popup_text = Paragraph::new(lines)
+        .block(popup_block)
+        .style(Style::default().bg(colors.background).fg(colors.text));
+
+    f.render_widget(popup_text, popup_area);
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
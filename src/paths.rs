@@ -0,0 +1,54 @@
+use anyhow::{Context, Result};
+use std::{
+    env,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+const APP_DIR: &str = "clockradio";
+
+/// `$XDG_CONFIG_HOME/clockradio`, defaulting to `~/.config/clockradio`.
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// `$XDG_STATE_HOME/clockradio`, defaulting to `~/.local/state/clockradio`.
+pub fn state_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_STATE_HOME", ".local/state")
+}
+
+/// `$XDG_CACHE_HOME/clockradio`, defaulting to `~/.cache/clockradio`.
+pub fn cache_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CACHE_HOME", ".cache")
+}
+
+fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    let base = env::var_os(var)
+        .map(PathBuf::from)
+        // The spec says relative paths are invalid and must be ignored.
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(fallback)))?;
+    Some(base.join(APP_DIR))
+}
+
+/// Replaces `path` with `contents` atomically, creating its directory if
+/// needed, so a crash mid-write leaves the previous version intact.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = path.parent().context("file has no parent directory")?;
+    fs::create_dir_all(dir).with_context(|| format!("cannot create {}", dir.display()))?;
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let write = || -> std::io::Result<()> {
+        let mut file = File::create(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    };
+    write().or_else(|err| {
+        let _ = fs::remove_file(&tmp);
+        Err(err).with_context(|| format!("cannot write {}", path.display()))
+    })
+}
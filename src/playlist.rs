@@ -0,0 +1,224 @@
+//! Playlists in M3U, extended M3U and PLS format.
+
+use anyhow::{Context, Result, bail};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Entry {
+    /// Empty when the playlist did not name the entry.
+    pub title: String,
+    /// A URL, or a file path relative to the playlist.
+    pub location: String,
+}
+
+impl Entry {
+    pub fn is_url(&self) -> bool {
+        self.location.contains("://")
+    }
+}
+
+/// Reads a playlist file, failing if it lists nothing.
+pub fn load(path: &Path) -> Result<Vec<Entry>> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))?;
+    let entries = parse(&text);
+    if entries.is_empty() {
+        bail!("{} is an empty playlist", path.display());
+    }
+    Ok(entries)
+}
+
+/// Local files of a playlist, with relative paths resolved against the
+/// playlist's directory. URLs are left out.
+pub fn files(path: &Path) -> Result<Vec<PathBuf>> {
+    let base = path.parent().unwrap_or(Path::new(""));
+    let files: Vec<PathBuf> = load(path)?
+        .into_iter()
+        .filter_map(|entry| match entry.location.strip_prefix("file://") {
+            Some(file) => Some(PathBuf::from(file)),
+            None if entry.is_url() => None,
+            None => Some(base.join(entry.location)),
+        })
+        .collect();
+    if files.is_empty() {
+        bail!("{} lists no local files", path.display());
+    }
+    Ok(files)
+}
+
+/// Parses playlist text, detecting PLS by its `[playlist]` header and
+/// treating anything else as M3U.
+pub fn parse(text: &str) -> Vec<Entry> {
+    let text = text.trim_start_matches('\u{feff}');
+    let is_pls = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .is_some_and(|line| line.eq_ignore_ascii_case("[playlist]"));
+    if is_pls {
+        parse_pls(text)
+    } else {
+        parse_m3u(text)
+    }
+}
+
+/// `#EXTINF:<length>,<title>` names the entry on the next line.
+fn parse_m3u(text: &str) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut title = String::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            title = info
+                .split_once(',')
+                .map_or("", |(_, title)| title)
+                .trim()
+                .to_string();
+        } else if !line.is_empty() && !line.starts_with('#') {
+            entries.push(Entry {
+                title: std::mem::take(&mut title),
+                location: line.to_string(),
+            });
+        }
+    }
+    entries
+}
+
+/// `FileN=` and `TitleN=` pairs, ordered by N.
+fn parse_pls(text: &str) -> Vec<Entry> {
+    let mut entries: BTreeMap<u32, Entry> = BTreeMap::new();
+    for line in text.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim().to_string();
+        if let Some(index) = key.strip_prefix("file").and_then(|n| n.parse().ok()) {
+            entries.entry(index).or_default().location = value;
+        } else if let Some(index) = key.strip_prefix("title").and_then(|n| n.parse().ok()) {
+            entries.entry(index).or_default().title = value;
+        }
+    }
+    entries
+        .into_values()
+        .filter(|entry| !entry.location.is_empty())
+        .collect()
+}
+
+/// Writes an extended M3U playlist.
+pub fn to_m3u(entries: &[Entry]) -> String {
+    let mut text = "#EXTM3U\n".to_string();
+    for entry in entries {
+        if !entry.title.is_empty() {
+            let _ = writeln!(text, "#EXTINF:-1,{}", entry.title);
+        }
+        let _ = writeln!(text, "{}", entry.location);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn entry(title: &str, location: &str) -> Entry {
+        Entry {
+            title: title.to_string(),
+            location: location.to_string(),
+        }
+    }
+
+    #[test]
+    fn reads_extended_m3u() {
+        let text = "\u{feff}#EXTM3U\r\n\
+                    #EXTINF:-1,Test FM\r\n\
+                    http://radio.example.com/live\r\n\
+                    \r\n\
+                    # A comment\r\n\
+                    #EXTINF:213, Artist - Song \r\n\
+                    music/song.mp3\r\n\
+                    /srv/untitled.ogg\r\n";
+        assert_eq!(
+            parse(text),
+            [
+                entry("Test FM", "http://radio.example.com/live"),
+                entry("Artist - Song", "music/song.mp3"),
+                entry("", "/srv/untitled.ogg"),
+            ]
+        );
+    }
+
+    #[test]
+    fn orders_pls_entries_by_number() {
+        let text = "\n[Playlist]\n\
+                    NumberOfEntries=3\n\
+                    Title2=Second\n\
+                    File10=http://radio.example.com/ten\n\
+                    File2=http://radio.example.com/two\n\
+                    File1 = http://radio.example.com/one\n\
+                    Title1 = First\n\
+                    Title3=No file\n\
+                    Version=2\n";
+        assert_eq!(
+            parse(text),
+            [
+                entry("First", "http://radio.example.com/one"),
+                entry("Second", "http://radio.example.com/two"),
+                entry("", "http://radio.example.com/ten"),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_files_next_to_the_playlist() {
+        let dir = env::temp_dir().join(format!("clockradio-{}-playlist", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("mix.m3u");
+        fs::write(
+            &path,
+            "http://radio.example.com/live\nsong.mp3\nfile:///srv/other.flac\n/srv/abs.wav\n",
+        )
+        .unwrap();
+        let resolved = files(&path);
+        let urls_only = dir.join("urls.m3u");
+        fs::write(&urls_only, "http://radio.example.com/live\n").unwrap();
+        let no_files = files(&urls_only).err().unwrap().to_string();
+        let empty = dir.join("empty.pls");
+        fs::write(&empty, "[playlist]\nNumberOfEntries=0\n").unwrap();
+        let no_entries = load(&empty).err().unwrap().to_string();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            resolved.unwrap(),
+            [
+                dir.join("song.mp3"),
+                PathBuf::from("/srv/other.flac"),
+                PathBuf::from("/srv/abs.wav"),
+            ]
+        );
+        assert!(no_files.ends_with("lists no local files"), "{no_files}");
+        assert!(no_entries.ends_with("is an empty playlist"), "{no_entries}");
+    }
+
+    #[test]
+    fn writes_what_it_reads() {
+        let entries = [
+            entry("Test FM", "http://radio.example.com/live"),
+            entry("", "http://radio.example.com/untitled"),
+        ];
+        let text = to_m3u(&entries);
+        assert_eq!(
+            text,
+            "#EXTM3U\n\
+             #EXTINF:-1,Test FM\n\
+             http://radio.example.com/live\n\
+             http://radio.example.com/untitled\n"
+        );
+        assert_eq!(parse(&text), entries);
+    }
+}
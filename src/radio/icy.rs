@@ -0,0 +1,199 @@
+//! ICY (Shoutcast/Icecast) in-band metadata.
+//!
+//! With `Icy-MetaData: 1` the server inserts a metadata block after every
+//! `icy-metaint` bytes of audio: one length byte counting 16-byte units,
+//! followed by text such as `StreamTitle='Artist - Song';`.
+
+use std::{
+    io::{self, ErrorKind, Read},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use super::StreamInfo;
+
+/// A stream that has sent nothing for this long is considered dead.
+const STALL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Strips metadata blocks from the audio and publishes the titles.
+pub struct IcyReader<R> {
+    inner: R,
+    /// Audio bytes between metadata blocks, `None` without metadata.
+    interval: Option<usize>,
+    /// Audio bytes left before the next metadata block.
+    remaining: usize,
+    info: Arc<Mutex<StreamInfo>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl<R: Read> IcyReader<R> {
+    pub fn new(
+        inner: R,
+        interval: Option<usize>,
+        info: Arc<Mutex<StreamInfo>>,
+        stop: Arc<AtomicBool>,
+    ) -> IcyReader<R> {
+        let interval = interval.filter(|&n| n > 0);
+        IcyReader {
+            inner,
+            interval,
+            remaining: interval.unwrap_or(0),
+            info,
+            stop,
+        }
+    }
+
+    /// Reads from the socket, riding out read timeouts until data arrives,
+    /// the stream stalls or playback is stopped.
+    fn read_inner(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let started = Instant::now();
+        loop {
+            if self.stop.load(Ordering::Relaxed) {
+                return Err(io::Error::other("stopped"));
+            }
+            match self.inner.read(buf) {
+                Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    if started.elapsed() > STALL_TIMEOUT {
+                        return Err(io::Error::new(ErrorKind::TimedOut, "stream stalled"));
+                    }
+                }
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                result => return result,
+            }
+        }
+    }
+
+    fn read_exact_inner(&mut self, mut buf: &mut [u8]) -> io::Result<()> {
+        while !buf.is_empty() {
+            match self.read_inner(buf)? {
+                0 => return Err(ErrorKind::UnexpectedEof.into()),
+                n => buf = &mut buf[n..],
+            }
+        }
+        Ok(())
+    }
+
+    fn read_metadata(&mut self) -> io::Result<()> {
+        let mut len = [0u8];
+        self.read_exact_inner(&mut len)?;
+        let mut block = vec![0u8; usize::from(len[0]) * 16];
+        self.read_exact_inner(&mut block)?;
+        if let Some(title) = stream_title(&String::from_utf8_lossy(&block)) {
+            let mut info = self.info.lock().unwrap();
+            info.title = (!title.is_empty()).then_some(title);
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for IcyReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(interval) = self.interval else {
+            return self.read_inner(buf);
+        };
+        if self.remaining == 0 {
+            self.read_metadata()?;
+            self.remaining = interval;
+        }
+        let len = buf.len().min(self.remaining);
+        let n = self.read_inner(&mut buf[..len])?;
+        self.remaining -= n;
+        Ok(n)
+    }
+}
+
+/// Extracts `StreamTitle` from a metadata block. Titles may contain quotes,
+/// so the value runs up to the `';` that ends the field.
+pub fn stream_title(block: &str) -> Option<String> {
+    let block = block.trim_end_matches('\0');
+    let start = block.find("StreamTitle='")? + "StreamTitle='".len();
+    let value = &block[start..];
+    let end = value
+        .find("';")
+        .unwrap_or(value.trim_end_matches('\'').len());
+    Some(value[..end].trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::radio::StreamState;
+
+    /// Hands out at most three bytes per read, like a slow socket.
+    struct Trickle(io::Cursor<Vec<u8>>);
+
+    impl Read for Trickle {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(3);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    fn info() -> Arc<Mutex<StreamInfo>> {
+        Arc::new(Mutex::new(StreamInfo {
+            url: String::new(),
+            state: StreamState::Connecting,
+            station: None,
+            title: None,
+        }))
+    }
+
+    #[test]
+    fn reads_the_stream_title() {
+        assert_eq!(
+            stream_title("StreamTitle='Artist - Song';StreamUrl='';\0\0\0").as_deref(),
+            Some("Artist - Song")
+        );
+        assert_eq!(
+            stream_title("StreamTitle='Don't Stop';").as_deref(),
+            Some("Don't Stop")
+        );
+        assert_eq!(
+            stream_title("StreamTitle='Unterminated'").as_deref(),
+            Some("Unterminated")
+        );
+        assert_eq!(stream_title("StreamTitle='';").as_deref(), Some(""));
+        assert_eq!(stream_title("StreamUrl='http://example.com';"), None);
+    }
+
+    #[test]
+    fn strips_metadata_across_reads() {
+        let mut block = b"StreamTitle='Song';".to_vec();
+        block.resize(32, 0);
+        let mut stream = b"abcde".to_vec();
+        stream.push(2);
+        stream.extend(&block);
+        stream.extend(b"fghij");
+        // An empty block keeps the title.
+        stream.push(0);
+        stream.extend(b"kl");
+
+        let info = info();
+        let mut reader = IcyReader::new(
+            Trickle(io::Cursor::new(stream)),
+            Some(5),
+            info.clone(),
+            Arc::new(AtomicBool::new(false)),
+        );
+        let mut audio = Vec::new();
+        reader.read_to_end(&mut audio).unwrap();
+        assert_eq!(audio, b"abcdefghijkl");
+        assert_eq!(info.lock().unwrap().title.as_deref(), Some("Song"));
+    }
+
+    #[test]
+    fn passes_audio_through_without_metadata() {
+        let mut reader = IcyReader::new(
+            Trickle(io::Cursor::new(b"abcdefgh".to_vec())),
+            Some(0),
+            info(),
+            Arc::new(AtomicBool::new(false)),
+        );
+        let mut audio = Vec::new();
+        reader.read_to_end(&mut audio).unwrap();
+        assert_eq!(audio, b"abcdefgh");
+    }
+}
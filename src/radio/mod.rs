@@ -0,0 +1,344 @@
+//! Internet radio: HTTP/Icecast/Shoutcast streams with ICY metadata.
+
+mod http;
+mod icy;
+mod presets;
+
+pub use presets::{MAX_PRESETS, Presets, Station, key_for_slot, slot_for_key};
+
+use crate::audio::{DecoderSource, Player, Ramp, Sink, Source, Volume};
+use anyhow::Result;
+use icy::IcyReader;
+use std::{
+    io::{Cursor, Read},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use symphonia::core::{io::ReadOnlySource, probe::Hint};
+
+/// Audio read ahead before decoding starts, about two seconds at 128 kbit/s.
+const PREBUFFER: usize = 32 * 1024;
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Playing this long counts as a good connection and resets the backoff.
+const STABLE: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StreamState {
+    Connecting,
+    /// Percentage of the prebuffer filled.
+    Buffering(u8),
+    Playing,
+    Reconnecting {
+        attempt: u32,
+        error: String,
+    },
+    /// Gave up, the station is unreachable for good.
+    Failed(String),
+}
+
+impl StreamState {
+    /// Whether an alarm should stop waiting for a station that has been
+    /// silent for `silent` and ring the tone instead: at once when the
+    /// station is gone for good, otherwise after `timeout`.
+    pub fn should_fall_back(&self, silent: Duration, timeout: Duration) -> bool {
+        match self {
+            StreamState::Playing => false,
+            StreamState::Failed(_) => true,
+            _ => silent >= timeout,
+        }
+    }
+}
+
+/// What the UI shows about the stream.
+#[derive(Clone, Debug)]
+pub struct StreamInfo {
+    pub url: String,
+    pub state: StreamState,
+    /// Preset name, or else the `icy-name` header.
+    pub station: Option<String>,
+    /// Latest `StreamTitle`, usually "Artist - Song".
+    pub title: Option<String>,
+}
+
+/// Decodes a network stream, reconnecting with backoff when it drops.
+struct StreamSource {
+    url: String,
+    info: Arc<Mutex<StreamInfo>>,
+    stop: Arc<AtomicBool>,
+    decoder: Option<DecoderSource>,
+    attempt: u32,
+    playing_since: Option<Instant>,
+}
+
+impl StreamSource {
+    fn set_state(&self, state: StreamState) {
+        self.info.lock().unwrap().state = state;
+    }
+
+    fn connect(&self) -> Result<DecoderSource> {
+        self.set_state(StreamState::Connecting);
+        let response = http::get(&self.url)?;
+
+        let mut hint = Hint::new();
+        match response.header("content-type") {
+            Some(mime) if mime.contains("ogg") => hint.with_extension("ogg"),
+            Some(mime) if mime.contains("aac") => hint.with_extension("aac"),
+            Some(mime) if mime.contains("flac") => hint.with_extension("flac"),
+            _ => hint.with_extension("mp3"),
+        };
+        {
+            // A preset's own name wins over what the server calls itself.
+            let mut info = self.info.lock().unwrap();
+            if info.station.is_none() {
+                info.station = response.header("icy-name").map(str::to_string);
+            }
+        }
+
+        let interval = response.header("icy-metaint").and_then(|n| n.parse().ok());
+        let mut reader = IcyReader::new(
+            response.body,
+            interval,
+            self.info.clone(),
+            self.stop.clone(),
+        );
+
+        let mut prebuffer = vec![0u8; PREBUFFER];
+        let mut filled = 0;
+        while filled < PREBUFFER {
+            self.set_state(StreamState::Buffering((filled * 100 / PREBUFFER) as u8));
+            match reader.read(&mut prebuffer[filled..])? {
+                0 => anyhow::bail!("stream ended while buffering"),
+                n => filled += n,
+            }
+        }
+
+        let stream = Cursor::new(prebuffer).chain(reader);
+        DecoderSource::new(Box::new(ReadOnlySource::new(stream)), &hint)
+    }
+
+    /// Sleeps before the next attempt, waking early to notice a stop.
+    fn back_off(&self) {
+        let delay = Duration::from_secs(1 << self.attempt.min(5)).min(MAX_BACKOFF);
+        let until = Instant::now() + delay;
+        while Instant::now() < until && !self.stop.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+impl Source for StreamSource {
+    fn fill(&mut self, buf: &mut Vec<f32>) -> Result<bool> {
+        loop {
+            if self.stop.load(Ordering::Relaxed) {
+                return Ok(false);
+            }
+
+            let error = match &mut self.decoder {
+                Some(decoder) => match decoder.fill(buf) {
+                    Ok(true) => {
+                        let since = *self.playing_since.get_or_insert_with(Instant::now);
+                        if since.elapsed() > STABLE {
+                            self.attempt = 0;
+                        }
+                        self.set_state(StreamState::Playing);
+                        return Ok(true);
+                    }
+                    Ok(false) => "stream ended".to_string(),
+                    Err(err) => format!("{err:#}"),
+                },
+                None => match self.connect() {
+                    Ok(decoder) => {
+                        self.decoder = Some(decoder);
+                        continue;
+                    }
+                    Err(err) if err.is::<http::Fatal>() => {
+                        self.set_state(StreamState::Failed(format!("{err:#}")));
+                        return Ok(false);
+                    }
+                    Err(err) => format!("{err:#}"),
+                },
+            };
+
+            if self.stop.load(Ordering::Relaxed) {
+                return Ok(false);
+            }
+            self.decoder = None;
+            self.playing_since = None;
+            self.attempt += 1;
+            self.set_state(StreamState::Reconnecting {
+                attempt: self.attempt,
+                error,
+            });
+            self.back_off();
+        }
+    }
+}
+
+/// A station playing in the background.
+pub struct Radio {
+    info: Arc<Mutex<StreamInfo>>,
+    stop: Arc<AtomicBool>,
+    player: Player,
+}
+
+impl Radio {
+    pub fn start(station: &Station, volume: Volume, sink: Box<dyn Sink>) -> Radio {
+        let url = station.url.as_str();
+        let info = Arc::new(Mutex::new(StreamInfo {
+            url: url.to_string(),
+            state: StreamState::Connecting,
+            station: (!station.name.is_empty()).then(|| station.name.clone()),
+            title: None,
+        }));
+        let stop = Arc::new(AtomicBool::new(false));
+        let source = StreamSource {
+            url: url.to_string(),
+            info: info.clone(),
+            stop: stop.clone(),
+            decoder: None,
+            attempt: 0,
+            playing_since: None,
+        };
+        Radio {
+            info,
+            stop,
+            player: Player::start(Box::new(Ramp::new(Box::new(source), volume)), sink),
+        }
+    }
+
+    pub fn info(&self) -> StreamInfo {
+        self.info.lock().unwrap().clone()
+    }
+
+    pub fn set_volume(&self, volume: f32) {
+        self.player.set_volume(volume);
+    }
+}
+
+impl Drop for Radio {
+    fn drop(&mut self) {
+        // Connecting can block for seconds, too long to hold up the UI. The
+        // stream notices the flag once the wait is over and winds down.
+        self.stop.store(true, Ordering::Relaxed);
+        self.player.detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io::Write, net::TcpListener, sync::mpsc};
+
+    struct Discard;
+
+    impl Sink for Discard {
+        fn write(&mut self, _samples: &[f32]) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// About ten seconds of silent MPEG-1 Layer III at 128 kbit/s, 44.1 kHz
+    /// stereo: a header and all-zero side information and main data.
+    fn silent_mp3() -> Vec<u8> {
+        let mut frame = vec![0u8; 417];
+        frame[..4].copy_from_slice(&[0xff, 0xfb, 0x90, 0x00]);
+        frame.repeat(400)
+    }
+
+    /// Serves `audio` once as a Shoutcast stream with a title every
+    /// `interval` bytes, then holds the connection open until `done`.
+    fn serve(audio: Vec<u8>, interval: usize, done: mpsc::Receiver<()>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/live", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut byte = [0u8];
+            while !request.ends_with(b"\r\n\r\n") && socket.read(&mut byte).unwrap() == 1 {
+                request.push(byte[0]);
+            }
+            assert!(request.starts_with(b"GET /live HTTP/1.0\r\n"));
+
+            let mut block = b"StreamTitle='Artist - It's a Song';".to_vec();
+            block.resize(block.len().div_ceil(16) * 16, 0);
+            let mut stream = format!(
+                "ICY 200 OK\r\nicy-name: Test FM\r\nicy-metaint: {interval}\r\n\
+                 content-type: audio/mpeg\r\n\r\n"
+            )
+            .into_bytes();
+            for chunk in audio.chunks(interval) {
+                stream.extend(chunk);
+                stream.push((block.len() / 16) as u8);
+                stream.extend(&block);
+            }
+            let _ = socket.write_all(&stream);
+            let _ = done.recv();
+        });
+        url
+    }
+
+    #[test]
+    fn plays_a_stream_with_titles() {
+        let (done, wait) = mpsc::channel();
+        let station = Station {
+            name: String::new(),
+            url: serve(silent_mp3(), 8192, wait),
+        };
+        let radio = Radio::start(&station, Volume::default(), Box::new(Discard));
+        let started = Instant::now();
+        while radio.info().state != StreamState::Playing {
+            assert!(
+                started.elapsed() < Duration::from_secs(10),
+                "{:?}",
+                radio.info().state
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+        let info = radio.info();
+        assert_eq!(info.station.as_deref(), Some("Test FM"));
+        assert_eq!(info.title.as_deref(), Some("Artist - It's a Song"));
+        drop(radio);
+        drop(done);
+    }
+
+    #[test]
+    fn falls_back_on_dead_or_silent_stations() {
+        let timeout = Duration::from_secs(15);
+        let after = Duration::from_secs;
+        let reconnecting = StreamState::Reconnecting {
+            attempt: 2,
+            error: "stream ended".to_string(),
+        };
+        assert!(!StreamState::Playing.should_fall_back(after(60), timeout));
+        assert!(
+            StreamState::Failed("404 Not Found".to_string()).should_fall_back(after(0), timeout)
+        );
+        for state in [
+            StreamState::Connecting,
+            StreamState::Buffering(40),
+            reconnecting,
+        ] {
+            assert!(!state.should_fall_back(after(14), timeout), "{state:?}");
+            assert!(state.should_fall_back(after(15), timeout), "{state:?}");
+        }
+    }
+
+    #[test]
+    fn stops_without_waiting_for_a_slow_server() {
+        // Accepts connections but never answers.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let station = Station {
+            name: String::new(),
+            url: format!("http://{}/", listener.local_addr().unwrap()),
+        };
+        let radio = Radio::start(&station, Volume::default(), Box::new(Discard));
+        thread::sleep(Duration::from_millis(100));
+        let started = Instant::now();
+        drop(radio);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
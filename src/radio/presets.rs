@@ -0,0 +1,187 @@
+use super::http;
+use crate::playlist::{self, Entry};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// Presets are bound to the number keys 1-9 and 0.
+pub const MAX_PRESETS: usize = 10;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Station {
+    /// Empty when the playlist did not name the station.
+    #[serde(default)]
+    pub name: String,
+    pub url: String,
+}
+
+/// Saved stations, in key order.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Presets {
+    stations: Vec<Station>,
+}
+
+impl Presets {
+    pub fn len(&self) -> usize {
+        self.stations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stations.is_empty()
+    }
+
+    pub fn get(&self, slot: usize) -> Option<&Station> {
+        self.stations.get(slot)
+    }
+
+    /// Slot of the preset playing `url`, if any.
+    pub fn slot_of(&self, url: &str) -> Option<usize> {
+        self.stations.iter().position(|station| station.url == url)
+    }
+
+    /// Replaces the presets with the stations of a playlist file. Returns
+    /// how many stations did not fit.
+    pub fn import(&mut self, path: &Path) -> Result<usize> {
+        let mut stations: Vec<Station> = playlist::load(path)?
+            .into_iter()
+            .filter(Entry::is_url)
+            .map(|entry| Station {
+                name: entry.title,
+                url: entry.location,
+            })
+            .collect();
+        if stations.is_empty() {
+            bail!("{} lists no stream URLs", path.display());
+        }
+        let dropped = stations.len().saturating_sub(MAX_PRESETS);
+        stations.truncate(MAX_PRESETS);
+        self.stations = stations;
+        Ok(dropped)
+    }
+
+    /// Slots whose URL the stream client cannot open, with the reason,
+    /// such as https-only stations.
+    pub fn unplayable(&self) -> Vec<(usize, String)> {
+        self.stations
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, station)| {
+                http::Url::parse(&station.url)
+                    .err()
+                    .map(|err| (slot, err.to_string()))
+            })
+            .collect()
+    }
+
+    /// Writes the presets as an extended M3U playlist.
+    pub fn export(&self, path: &Path) -> Result<()> {
+        let entries: Vec<Entry> = self
+            .stations
+            .iter()
+            .map(|station| Entry {
+                title: station.name.clone(),
+                location: station.url.clone(),
+            })
+            .collect();
+        fs::write(path, playlist::to_m3u(&entries))
+            .with_context(|| format!("cannot write {}", path.display()))
+    }
+}
+
+/// Slot bound to a number key, `'1'` being the first and `'0'` the tenth.
+pub fn slot_for_key(key: char) -> Option<usize> {
+    match key.to_digit(10)? {
+        0 => Some(9),
+        n => Some(n as usize - 1),
+    }
+}
+
+/// Number key of a slot, the inverse of [`slot_for_key`].
+pub fn key_for_slot(slot: usize) -> char {
+    char::from_digit(((slot + 1) % 10) as u32, 10).unwrap_or('?')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = env::temp_dir().join(format!("clockradio-{}-{name}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn imports_what_it_exported() {
+        let dir = temp_dir("presets");
+        let path = dir.join("stations.m3u");
+        let presets: Presets = serde_json::from_str(
+            r#"[{"name": "Test FM", "url": "http://radio.example.com/live"},
+                {"url": "http://radio.example.com/untitled"}]"#,
+        )
+        .unwrap();
+        presets.export(&path).unwrap();
+        let mut imported = Presets::default();
+        let dropped = imported.import(&path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(dropped.unwrap(), 0);
+        assert_eq!(imported.stations, presets.stations);
+    }
+
+    #[test]
+    fn keeps_the_stations_that_fit_on_the_keys() {
+        let dir = temp_dir("presets-many");
+        let path = dir.join("many.pls");
+        let mut pls = "[playlist]\n".to_string();
+        for n in 1..=12 {
+            pls.push_str(&format!(
+                "File{n}=http://radio.example.com/{n}\nTitle{n}=Station {n}\n"
+            ));
+        }
+        pls.push_str("File13=local.mp3\n");
+        fs::write(&path, pls).unwrap();
+        let mut presets = Presets::default();
+        let dropped = presets.import(&path);
+
+        fs::write(&path, "local.mp3\n").unwrap();
+        let no_urls = presets.import(&path).err().unwrap().to_string();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(dropped.unwrap(), 2);
+        assert_eq!(presets.len(), MAX_PRESETS);
+        assert_eq!(presets.get(9).unwrap().name, "Station 10");
+        assert_eq!(presets.slot_of("http://radio.example.com/3"), Some(2));
+        assert!(no_urls.ends_with("lists no stream URLs"), "{no_urls}");
+        // A failed import keeps the old presets.
+        assert_eq!(presets.len(), MAX_PRESETS);
+    }
+
+    #[test]
+    fn flags_stations_the_client_cannot_open() {
+        let presets: Presets = serde_json::from_str(
+            r#"[{"url": "http://radio.example.com/live"},
+                {"url": "https://radio.example.com/secure"},
+                {"url": "http://radio.example.com:8000/"}]"#,
+        )
+        .unwrap();
+        let unplayable = presets.unplayable();
+        assert_eq!(unplayable.len(), 1);
+        assert_eq!(unplayable[0].0, 1);
+        assert!(unplayable[0].1.contains("https"), "{}", unplayable[0].1);
+    }
+
+    #[test]
+    fn binds_slots_to_number_keys() {
+        assert_eq!(slot_for_key('1'), Some(0));
+        assert_eq!(slot_for_key('9'), Some(8));
+        assert_eq!(slot_for_key('0'), Some(9));
+        assert_eq!(slot_for_key('a'), None);
+        for slot in 0..MAX_PRESETS {
+            assert_eq!(slot_for_key(key_for_slot(slot)), Some(slot));
+        }
+        assert_eq!(key_for_slot(9), '0');
+    }
+}
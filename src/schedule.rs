@@ -0,0 +1,334 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeDelta, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+use std::{fmt, num::NonZeroU32};
+
+/// On which days an alarm rings.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Schedule {
+    Once,
+    Daily,
+    Weekdays,
+    Weekends,
+    Days(Vec<Weekday>),
+    /// Every `n` days counting from `from`.
+    EveryNDays {
+        n: NonZeroU32,
+        from: NaiveDate,
+    },
+}
+
+/// Longest interval `every N days` takes.
+const MAX_INTERVAL: u32 = 365;
+
+const WEEK: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+impl Schedule {
+    pub fn is_recurring(&self) -> bool {
+        *self != Schedule::Once
+    }
+
+    fn matches(&self, date: NaiveDate) -> bool {
+        match self {
+            Schedule::Once | Schedule::Daily => true,
+            Schedule::Weekdays => date.weekday().number_from_monday() <= 5,
+            Schedule::Weekends => date.weekday().number_from_monday() > 5,
+            Schedule::Days(days) => days.contains(&date.weekday()),
+            Schedule::EveryNDays { n, from } => {
+                let since = (date - *from).num_days();
+                since >= 0 && since % i64::from(n.get()) == 0
+            }
+        }
+    }
+
+    /// First time after `after` that reads `time` on a day the schedule allows.
+    pub fn next_after<Tz: TimeZone>(
+        &self,
+        time: NaiveTime,
+        after: DateTime<Tz>,
+    ) -> Option<DateTime<Tz>> {
+        // The first day that could match and how far apart matching days
+        // are. Intervals are stepped through directly, so a state file with a
+        // huge one or a start date years away is no slower than a daily alarm.
+        let today = after.date_naive();
+        let (mut date, step, days) = match self {
+            Schedule::EveryNDays { n, from } => {
+                let n = i64::from(n.get());
+                let since = (today - *from).num_days();
+                let first = match since {
+                    ..=0 => *from,
+                    since => today.checked_add_signed(TimeDelta::days((n - since % n) % n))?,
+                };
+                (first, n, 3)
+            }
+            _ => (today, 1, 9),
+        };
+        // Beyond a full cycle, one more for the time having passed on the
+        // first day and one for a DST gap swallowing it.
+        for _ in 0..days {
+            if self.matches(date)
+                && let Some(at) = date
+                    .and_time(time)
+                    .and_local_timezone(after.timezone())
+                    .earliest()
+                && at > after
+            {
+                return Some(at);
+            }
+            date = date.checked_add_signed(TimeDelta::days(step))?;
+        }
+        None
+    }
+
+    /// Parses the editor syntax: `once`, `daily`, `weekdays`, `weekends`,
+    /// a day list such as `mon,wed,fri`, or `every 3 days`. Intervals are
+    /// counted from `today`.
+    pub fn parse(input: &str, today: NaiveDate) -> Result<Schedule, String> {
+        let input = input.trim().to_lowercase();
+        match input.as_str() {
+            "" | "once" => return Ok(Schedule::Once),
+            "daily" | "every day" => return Ok(Schedule::Daily),
+            "weekdays" => return Ok(Schedule::Weekdays),
+            "weekends" => return Ok(Schedule::Weekends),
+            _ => {}
+        }
+
+        if let Some(rest) = input.strip_prefix("every ") {
+            let count = rest.trim_end_matches("days").trim_end_matches('d').trim();
+            return match count.parse::<NonZeroU32>() {
+                Err(_) => Err(format!("Cannot parse interval \"{rest}\"")),
+                Ok(n) if n.get() > MAX_INTERVAL => {
+                    Err(format!("Intervals go up to {MAX_INTERVAL} days"))
+                }
+                Ok(n) if n.get() == 1 => Ok(Schedule::Daily),
+                Ok(n) => Ok(Schedule::EveryNDays { n, from: today }),
+            };
+        }
+
+        let mut days = Vec::new();
+        for part in input.split(',') {
+            let day: Weekday = part
+                .trim()
+                .parse()
+                .map_err(|_| format!("Unknown day \"{}\"", part.trim()))?;
+            if !days.contains(&day) {
+                days.push(day);
+            }
+        }
+        days.sort_by_key(|d| d.num_days_from_monday());
+        Ok(match days.len() {
+            7 => Schedule::Daily,
+            _ => Schedule::Days(days),
+        })
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Schedule::Once => write!(f, "once"),
+            Schedule::Daily => write!(f, "daily"),
+            Schedule::Weekdays => write!(f, "weekdays"),
+            Schedule::Weekends => write!(f, "weekends"),
+            Schedule::Days(days) => {
+                let names: Vec<String> = WEEK
+                    .iter()
+                    .filter(|d| days.contains(d))
+                    .map(|d| d.to_string().to_lowercase())
+                    .collect();
+                write!(f, "{}", names.join(","))
+            }
+            Schedule::EveryNDays { n, .. } => write!(f, "every {n} days"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, LocalResult, NaiveDateTime};
+
+    /// Central European time around the 2026 spring change, when clocks
+    /// jump from 02:00 to 03:00 on 29 March.
+    #[derive(Clone, Copy, Debug)]
+    struct Spring;
+
+    impl Spring {
+        fn switch() -> NaiveDateTime {
+            NaiveDate::from_ymd_opt(2026, 3, 29)
+                .unwrap()
+                .and_hms_opt(1, 0, 0)
+                .unwrap()
+        }
+
+        fn offset(summer: bool) -> FixedOffset {
+            FixedOffset::east_opt(if summer { 7200 } else { 3600 }).unwrap()
+        }
+    }
+
+    impl TimeZone for Spring {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> Spring {
+            Spring
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            LocalResult::Single(Spring::offset(*local > Spring::switch().date()))
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let winter = *local - TimeDelta::hours(1) < Spring::switch();
+            let summer = *local - TimeDelta::hours(2) >= Spring::switch();
+            match (winter, summer) {
+                (true, true) => LocalResult::Ambiguous(Spring::offset(false), Spring::offset(true)),
+                (true, false) => LocalResult::Single(Spring::offset(false)),
+                (false, true) => LocalResult::Single(Spring::offset(true)),
+                (false, false) => LocalResult::None,
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            Spring::offset(*utc > Spring::switch().date())
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            Spring::offset(*utc >= Spring::switch())
+        }
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<FixedOffset> {
+        Spring::offset(true)
+            .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn seven() -> NaiveTime {
+        NaiveTime::from_hms_opt(7, 0, 0).unwrap()
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 15).unwrap()
+    }
+
+    #[test]
+    fn rejects_a_zero_interval_when_loading() {
+        let json = r#"{"every_n_days":{"n":0,"from":"2026-10-15"}}"#;
+        assert!(serde_json::from_str::<Schedule>(json).is_err());
+        let json = r#"{"every_n_days":{"n":3,"from":"2026-10-15"}}"#;
+        assert!(serde_json::from_str::<Schedule>(json).is_ok());
+    }
+
+    #[test]
+    fn parses_the_editor_syntax() {
+        let parse = |input| Schedule::parse(input, today());
+        assert_eq!(parse(""), Ok(Schedule::Once));
+        assert_eq!(parse("Every day"), Ok(Schedule::Daily));
+        assert_eq!(parse("every 1 days"), Ok(Schedule::Daily));
+        assert_eq!(
+            parse("fri, mon,wed,mon"),
+            Ok(Schedule::Days(vec![
+                Weekday::Mon,
+                Weekday::Wed,
+                Weekday::Fri
+            ]))
+        );
+        assert_eq!(parse("mon,tue,wed,thu,fri,sat,sun"), Ok(Schedule::Daily));
+        let every_3 = parse("every 3 days").unwrap();
+        assert_eq!(
+            every_3,
+            Schedule::EveryNDays {
+                n: NonZeroU32::new(3).unwrap(),
+                from: today(),
+            }
+        );
+        assert_eq!(every_3.to_string(), "every 3 days");
+        assert!(parse("every 365 days").is_ok());
+        assert!(parse("every 366 days").is_err());
+        assert!(parse("every 4000000000 days").is_err());
+        assert!(parse("every 0 days").is_err());
+        assert!(parse("mon,someday").is_err());
+    }
+
+    #[test]
+    fn finds_the_next_allowed_day() {
+        // Thursday 15 October 2026, after the alarm time.
+        let after = at(15, 8, 0);
+        let next = |schedule: Schedule| schedule.next_after(seven(), after);
+        assert_eq!(next(Schedule::Once), Some(at(16, 7, 0)));
+        assert_eq!(next(Schedule::Weekdays), Some(at(16, 7, 0)));
+        assert_eq!(next(Schedule::Weekends), Some(at(17, 7, 0)));
+        assert_eq!(
+            next(Schedule::Days(vec![Weekday::Mon, Weekday::Thu])),
+            Some(at(19, 7, 0))
+        );
+        // Still ahead today.
+        assert_eq!(
+            Schedule::Weekdays.next_after(seven(), at(15, 6, 59)),
+            Some(at(15, 7, 0))
+        );
+        // From Friday evening, weekdays resume on Monday.
+        assert_eq!(
+            Schedule::Weekdays.next_after(seven(), at(16, 20, 0)),
+            Some(at(19, 7, 0))
+        );
+    }
+
+    #[test]
+    fn counts_intervals_from_their_first_day() {
+        let every_3 = |from| Schedule::EveryNDays {
+            n: NonZeroU32::new(3).unwrap(),
+            from,
+        };
+        let schedule = every_3(today());
+        assert_eq!(
+            schedule.next_after(seven(), at(15, 6, 0)),
+            Some(at(15, 7, 0))
+        );
+        assert_eq!(
+            schedule.next_after(seven(), at(15, 8, 0)),
+            Some(at(18, 7, 0))
+        );
+        assert_eq!(
+            schedule.next_after(seven(), at(18, 7, 0)),
+            Some(at(21, 7, 0))
+        );
+        // Not before the first day, however far off.
+        let later = every_3(NaiveDate::from_ymd_opt(2026, 10, 28).unwrap());
+        assert_eq!(later.next_after(seven(), at(15, 8, 0)), Some(at(28, 7, 0)));
+    }
+
+    #[test]
+    fn steps_through_any_interval_from_the_state_file() {
+        let json = r#"{"every_n_days":{"n":4294967295,"from":"2026-10-14"}}"#;
+        let schedule: Schedule = serde_json::from_str(json).unwrap();
+        assert_eq!(schedule.next_after(seven(), at(15, 8, 0)), None);
+        let json = r#"{"every_n_days":{"n":1000,"from":"+262000-01-01"}}"#;
+        let schedule: Schedule = serde_json::from_str(json).unwrap();
+        let next = schedule.next_after(seven(), at(15, 8, 0)).unwrap();
+        assert_eq!(
+            next.date_naive(),
+            NaiveDate::from_ymd_opt(262_000, 1, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn moves_on_when_the_time_falls_in_a_dst_gap() {
+        let half_past_two = NaiveTime::from_hms_opt(2, 30, 0).unwrap();
+        let after = Spring.with_ymd_and_hms(2026, 3, 28, 12, 0, 0).unwrap();
+        let next = Schedule::Daily.next_after(half_past_two, after).unwrap();
+        assert_eq!(
+            next,
+            Spring.with_ymd_and_hms(2026, 3, 30, 2, 30, 0).unwrap()
+        );
+        assert_eq!(next.offset(), &Spring::offset(true));
+    }
+}
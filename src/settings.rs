@@ -0,0 +1,26 @@
+use crate::face::Face;
+use serde::{Deserialize, Serialize};
+
+/// Preferences the user changes from inside the app.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub snooze_minutes: u32,
+    /// A ringing alarm nobody reacts to stops and counts as missed after this long.
+    pub ring_timeout_minutes: u32,
+    /// How the clock shows the time.
+    pub face: Face,
+    /// Name of the color theme, the first one when unset.
+    pub theme: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            snooze_minutes: 9,
+            ring_timeout_minutes: 15,
+            face: Face::Digital,
+            theme: None,
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! The sleep timer: the radio plays for a while, fades out and stops.
+
+use chrono::TimeDelta;
+
+/// Sleep timer lengths 's' cycles through, in minutes.
+const STEPS: [u32; 5] = [15, 30, 45, 60, 90];
+/// Longest timer the prompt takes, twelve hours.
+const MAX_MINUTES: u32 = 720;
+/// The radio fades out over the last stretch of the sleep timer.
+const FADE: TimeDelta = TimeDelta::minutes(1);
+
+/// The step after a timer of `current` minutes, `None` to turn it off after
+/// the longest.
+pub fn next_step(current: Option<u32>) -> Option<u32> {
+    STEPS
+        .into_iter()
+        .find(|&minutes| current.is_none_or(|current| minutes > current))
+}
+
+/// Parses the minutes typed at the sleep prompt. Nothing or `0` turns the
+/// timer off.
+pub fn parse_minutes(input: &str) -> Result<Option<u32>, String> {
+    match input.trim() {
+        "" | "0" => Ok(None),
+        minutes => match minutes.parse() {
+            Ok(minutes @ 1..=MAX_MINUTES) => Ok(Some(minutes)),
+            _ => Err(format!("Sleep for 1 to {MAX_MINUTES} minutes")),
+        },
+    }
+}
+
+/// Radio volume with `left` on the timer, `None` once it has run out.
+pub fn volume(left: TimeDelta) -> Option<f32> {
+    (left > TimeDelta::zero())
+        .then(|| (left.num_milliseconds() as f32 / FADE.num_milliseconds() as f32).min(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycles_through_the_steps_then_off() {
+        let mut current = None;
+        let mut seen = Vec::new();
+        for _ in 0..6 {
+            current = next_step(current);
+            seen.push(current);
+        }
+        assert_eq!(
+            seen,
+            [Some(15), Some(30), Some(45), Some(60), Some(90), None]
+        );
+        // A custom length moves on to the next longer step.
+        assert_eq!(next_step(Some(20)), Some(30));
+        assert_eq!(next_step(Some(500)), None);
+    }
+
+    #[test]
+    fn takes_one_to_720_minutes() {
+        assert_eq!(parse_minutes(""), Ok(None));
+        assert_eq!(parse_minutes(" 0 "), Ok(None));
+        assert_eq!(parse_minutes("1"), Ok(Some(1)));
+        assert_eq!(parse_minutes("720"), Ok(Some(720)));
+        let error = Err("Sleep for 1 to 720 minutes".to_string());
+        assert_eq!(parse_minutes("721"), error);
+        assert_eq!(parse_minutes("-5"), error);
+        assert_eq!(parse_minutes("1h"), error);
+    }
+
+    #[test]
+    fn fades_out_over_the_last_minute() {
+        assert_eq!(volume(TimeDelta::minutes(30)), Some(1.0));
+        assert_eq!(volume(TimeDelta::seconds(60)), Some(1.0));
+        assert_eq!(volume(TimeDelta::seconds(45)), Some(0.75));
+        assert_eq!(volume(TimeDelta::seconds(15)), Some(0.25));
+        assert_eq!(volume(TimeDelta::milliseconds(1)), Some(1.0 / 60_000.0));
+        assert_eq!(volume(TimeDelta::zero()), None);
+        assert_eq!(volume(TimeDelta::seconds(-3)), None);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{alarm::AlarmList, paths, radio::Presets, settings::Settings};
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::{fs, io::ErrorKind, path::PathBuf};
+
+/// Everything that survives a restart.
+#[derive(Default, Deserialize)]
+pub struct State {
+    #[serde(default)]
+    pub settings: Settings,
+    #[serde(default)]
+    pub alarms: AlarmList,
+    #[serde(default)]
+    pub stations: Presets,
+}
+
+#[derive(Serialize)]
+struct StateRef<'a> {
+    settings: &'a Settings,
+    alarms: &'a AlarmList,
+    stations: &'a Presets,
+}
+
+/// JSON file holding the app [`State`].
+pub struct Store {
+    path: PathBuf,
+}
+
+impl Store {
+    pub fn new(path: PathBuf) -> Store {
+        Store { path }
+    }
+
+    /// `state.json` in the XDG state directory.
+    pub fn open_default() -> Result<Store> {
+        let dir = paths::state_dir().ok_or_else(|| {
+            anyhow!("cannot locate a state directory, set $HOME or $XDG_STATE_HOME")
+        })?;
+        Ok(Store::new(dir.join("state.json")))
+    }
+
+    /// Reads the saved state. A missing file is a fresh start, an unreadable
+    /// one is an error rather than silently losing the user's alarms.
+    pub fn load(&self) -> Result<State> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(State::default()),
+            Err(err) => {
+                return Err(err).with_context(|| format!("cannot read {}", self.path.display()));
+            }
+        };
+        serde_json::from_str(&text).with_context(|| {
+            format!(
+                "{} is corrupt, fix or remove it to start over",
+                self.path.display()
+            )
+        })
+    }
+
+    /// Replaces the state file atomically, so a crash mid-write leaves the
+    /// previous version intact.
+    pub fn save(&self, settings: &Settings, alarms: &AlarmList, stations: &Presets) -> Result<()> {
+        let json = serde_json::to_vec_pretty(&StateRef {
+            settings,
+            alarms,
+            stations,
+        })?;
+        paths::write_atomic(&self.path, &json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alarm::AlarmSpec,
+        audio::{Sound, Volume},
+        face::Face,
+        schedule::Schedule,
+    };
+    use chrono::{Local, NaiveTime, TimeZone};
+    use std::env;
+
+    /// A store in its own empty temporary directory.
+    fn store(name: &str) -> Store {
+        let dir = env::temp_dir().join(format!("clockradio-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        Store::new(dir.join("state.json"))
+    }
+
+    fn json(state: &State) -> serde_json::Value {
+        serde_json::to_value(StateRef {
+            settings: &state.settings,
+            alarms: &state.alarms,
+            stations: &state.stations,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn loads_what_it_saved() {
+        let store = store("round-trip");
+        let mut state = State::default();
+        state.settings.face = Face::Words;
+        state.settings.theme = Some("amber".to_string());
+        state.alarms.add(
+            AlarmSpec {
+                label: "Work".to_string(),
+                time: NaiveTime::from_hms_opt(6, 45, 0).unwrap(),
+                schedule: Schedule::Weekdays,
+                sound: Sound::Preset(2),
+                volume: Volume::parse("80% over 2m exp").unwrap(),
+                rule: None,
+            },
+            Local.with_ymd_and_hms(2026, 10, 15, 8, 0, 0).unwrap(),
+        );
+        state.stations = serde_json::from_str(
+            r#"[{"name": "Test FM", "url": "http://radio.example.com/live"}]"#,
+        )
+        .unwrap();
+
+        store
+            .save(&state.settings, &state.alarms, &state.stations)
+            .unwrap();
+        let loaded = store.load().unwrap();
+        fs::remove_dir_all(store.path.parent().unwrap()).unwrap();
+        assert_eq!(json(&loaded), json(&state));
+        assert_eq!(loaded.alarms.len(), 1);
+        assert_eq!(loaded.stations.get(0).unwrap().name, "Test FM");
+    }
+
+    #[test]
+    fn starts_fresh_without_a_file() {
+        let state = store("missing").load().unwrap();
+        assert_eq!(json(&state), json(&State::default()));
+    }
+
+    #[test]
+    fn reports_a_corrupt_file() {
+        let store = store("corrupt");
+        fs::create_dir_all(store.path.parent().unwrap()).unwrap();
+        fs::write(&store.path, r#"{"alarms": [{"id": "#).unwrap();
+        let error = store.load().err().unwrap();
+        fs::remove_dir_all(store.path.parent().unwrap()).unwrap();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "{} is corrupt, fix or remove it to start over",
+                store.path.display()
+            )
+        );
+    }
+
+    #[test]
+    fn cleans_up_after_a_failed_save() {
+        let store = store("failed");
+        // A directory in the way of the rename.
+        fs::create_dir_all(&store.path).unwrap();
+        let state = State::default();
+        let error = store
+            .save(&state.settings, &state.alarms, &state.stations)
+            .unwrap_err();
+        let tmp_left = store.path.with_extension("json.tmp").exists();
+        fs::remove_dir_all(store.path.parent().unwrap()).unwrap();
+        assert!(error.to_string().starts_with("cannot write"), "{error}");
+        assert!(!tmp_left);
+    }
+}
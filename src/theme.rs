@@ -0,0 +1,169 @@
+//! Color palettes. 't' switches between the built-in ones, the `[colors]`
+//! section of the config and any `.toml` files in the `themes` directory.
+
+use crate::color::ColorMode;
+use anyhow::{Context, Result};
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::{fs, io::ErrorKind, path::Path};
+
+pub const BUILT_IN: [&str; 5] = ["pink", "amber", "green", "red", "high-contrast"];
+
+/// Colors accept names (`"white"`), hex (`"#ff6b8a"`) or 256-color indices (`"208"`).
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    #[serde(skip)]
+    pub name: String,
+    /// Clock digits, borders, highlights and the ringing screen.
+    pub accent: Color,
+    pub text: Color,
+    pub background: Color,
+    /// Animated scenery and disabled entries.
+    pub dim: Color,
+    /// Error messages.
+    pub alert: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::built_in("pink").unwrap()
+    }
+}
+
+impl Theme {
+    pub fn built_in(name: &str) -> Option<Theme> {
+        let (accent, text, background, dim, alert) = match name {
+            "pink" => (
+                Color::Rgb(255, 107, 138),
+                Color::White,
+                Color::Black,
+                Color::Rgb(100, 100, 100),
+                Color::Red,
+            ),
+            // A vacuum fluorescent display.
+            "amber" => (
+                Color::Rgb(255, 176, 0),
+                Color::Rgb(255, 204, 102),
+                Color::Rgb(18, 10, 0),
+                Color::Rgb(110, 72, 0),
+                Color::Rgb(255, 80, 0),
+            ),
+            "green" => (
+                Color::Rgb(57, 255, 20),
+                Color::Rgb(180, 255, 160),
+                Color::Black,
+                Color::Rgb(30, 90, 20),
+                Color::Rgb(255, 220, 0),
+            ),
+            "red" => (
+                Color::Rgb(255, 42, 26),
+                Color::Rgb(255, 138, 128),
+                Color::Black,
+                Color::Rgb(90, 20, 16),
+                Color::Rgb(255, 208, 0),
+            ),
+            "high-contrast" => (
+                Color::LightYellow,
+                Color::White,
+                Color::Black,
+                Color::Gray,
+                Color::LightRed,
+            ),
+            _ => return None,
+        };
+        Some(Theme {
+            name: name.to_string(),
+            accent,
+            text,
+            background,
+            dim,
+            alert,
+        })
+    }
+
+    /// The theme in the colors the terminal has.
+    pub fn fit(&self, mode: ColorMode) -> Theme {
+        Theme {
+            name: self.name.clone(),
+            accent: mode.fit(self.accent),
+            text: mode.fit(self.text),
+            background: mode.fit(self.background),
+            dim: mode.fit(self.dim),
+            alert: mode.fit(self.alert),
+        }
+    }
+
+    /// Reads a theme file. Colors it leaves out are the pink theme's.
+    pub fn parse(name: &str, text: &str) -> Result<Theme> {
+        let theme: Theme = toml::from_str(text)?;
+        Ok(Theme {
+            name: name.to_string(),
+            ..theme
+        })
+    }
+}
+
+/// The built-in themes followed by the `.toml` files in `dir`, named after
+/// the file. A file with the name of a built-in theme replaces it.
+pub fn load_all(dir: Option<&Path>) -> Result<Vec<Theme>> {
+    let mut themes: Vec<Theme> = BUILT_IN
+        .iter()
+        .filter_map(|name| Theme::built_in(name))
+        .collect();
+    let Some(dir) = dir else {
+        return Ok(themes);
+    };
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(themes),
+        Err(err) => {
+            return Err(err).with_context(|| format!("cannot read {}", dir.display()));
+        }
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let name = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        let text =
+            fs::read_to_string(&path).with_context(|| format!("cannot read {}", path.display()))?;
+        let theme = Theme::parse(&name, &text)
+            .with_context(|| format!("invalid theme file {}", path.display()))?;
+        match themes.iter_mut().find(|theme| theme.name == name) {
+            Some(built_in) => *built_in = theme,
+            None => themes.push(theme),
+        }
+    }
+    Ok(themes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn has_every_built_in_theme() {
+        for name in BUILT_IN {
+            assert_eq!(Theme::built_in(name).unwrap().name, name);
+        }
+        assert_eq!(Theme::built_in("blue"), None);
+        assert_eq!(Theme::default().accent, Color::Rgb(255, 107, 138));
+    }
+
+    #[test]
+    fn fills_in_missing_colors() {
+        let theme = Theme::parse("ice", "accent = \"#a0e0ff\"\ndim = \"8\"").unwrap();
+        assert_eq!(theme.name, "ice");
+        assert_eq!(theme.accent, Color::Rgb(160, 224, 255));
+        assert_eq!(theme.dim, Color::Indexed(8));
+        assert_eq!(theme.text, Color::White);
+        assert!(Theme::parse("typo", "acent = \"red\"").is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! The last good report, kept on disk so a restart or an outage still has
+//! something to show.
+
+use super::{Report, WeatherConfig};
+use crate::paths;
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// Which settings produced the report; a cache for another place or
+    /// other units is ignored.
+    source: String,
+    fetched: DateTime<Local>,
+    report: Report,
+}
+
+pub struct Cache {
+    path: PathBuf,
+    source: String,
+}
+
+impl Cache {
+    /// `weather.json` in the XDG cache directory, if there is one.
+    pub fn open_default(config: &WeatherConfig) -> Option<Cache> {
+        let source = format!(
+            "{:?} {} {:?} {:?} {:?} {:?} {:?}",
+            config.provider,
+            config.location,
+            config.latitude,
+            config.longitude,
+            config.units,
+            config.file,
+            config.command
+        );
+        Some(Cache {
+            path: paths::cache_dir()?.join("weather.json"),
+            source,
+        })
+    }
+
+    /// The cached report and when it was fetched. Anything missing,
+    /// unreadable or from other settings is simply no cache.
+    pub fn load(&self) -> Option<(DateTime<Local>, Report)> {
+        let text = fs::read_to_string(&self.path).ok()?;
+        let entry: Entry = serde_json::from_str(&text).ok()?;
+        (entry.source == self.source).then_some((entry.fetched, entry.report))
+    }
+
+    pub fn save(&self, fetched: DateTime<Local>, report: &Report) -> Result<()> {
+        let json = serde_json::to_vec(&EntryRef {
+            source: &self.source,
+            fetched,
+            report,
+        })?;
+        paths::write_atomic(&self.path, &json)
+    }
+}
+
+#[derive(Serialize)]
+struct EntryRef<'a> {
+    source: &'a str,
+    fetched: DateTime<Local>,
+    report: &'a Report,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::env;
+
+    fn report() -> Report {
+        serde_json::from_str(include_str!("fixtures/local.json")).unwrap()
+    }
+
+    /// A cache in its own temporary directory.
+    fn cache(name: &str, source: &str) -> Cache {
+        let dir = env::temp_dir().join(format!("clockradio-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        Cache {
+            path: dir.join("weather.json"),
+            source: source.to_string(),
+        }
+    }
+
+    #[test]
+    fn loads_what_it_saved() {
+        let cache = cache("round-trip", "London");
+        assert!(cache.load().is_none());
+        let fetched = Local.with_ymd_and_hms(2026, 10, 15, 6, 30, 0).unwrap();
+        cache.save(fetched, &report()).unwrap();
+        let (loaded, report) = cache.load().unwrap();
+        fs::remove_dir_all(cache.path.parent().unwrap()).unwrap();
+        assert_eq!(loaded, fetched);
+        assert_eq!(report.current.place, "Home");
+        assert_eq!(report.hourly.len(), 2);
+    }
+
+    #[test]
+    fn ignores_other_settings() {
+        let london = cache("source", "London");
+        let now = Local::now();
+        london.save(now, &report()).unwrap();
+        let paris = Cache {
+            path: london.path.clone(),
+            source: "Paris".to_string(),
+        };
+        assert!(paris.load().is_none());
+        assert!(london.load().is_some());
+        fs::remove_dir_all(london.path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn ignores_a_corrupt_file() {
+        let cache = cache("corrupt", "London");
+        cache.save(Local::now(), &report()).unwrap();
+        fs::write(&cache.path, "{\"source\": \"London\", \"fetched\":").unwrap();
+        assert!(cache.load().is_none());
+        fs::remove_dir_all(cache.path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn reports_failed_saves() {
+        let blocker = cache("blocked", "London");
+        let dir = blocker.path.parent().unwrap();
+        // A file where the cache directory should be.
+        fs::write(dir, "").unwrap();
+        let cache = Cache {
+            path: dir.join("weather.json"),
+            source: "London".to_string(),
+        };
+        let error = cache.save(Local::now(), &report()).unwrap_err();
+        fs::remove_file(dir).unwrap();
+        assert!(error.to_string().starts_with("cannot create"), "{error}");
+    }
+}
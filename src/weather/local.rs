@@ -0,0 +1,108 @@
+//! Reports in clockradio's own JSON format, from a file or a command's
+//! output, for stations and scripts that already have the data.
+
+use super::{Fetch, Report, WeatherProvider};
+use anyhow::{Context, Result, bail};
+use std::{fs, path::PathBuf, process::Command};
+
+#[derive(Clone)]
+pub enum Local {
+    File(PathBuf),
+    /// Run with `sh -c`; its standard output is the report.
+    Command(String),
+}
+
+impl Local {
+    fn read(&self) -> Result<Report> {
+        let (text, source) = match self {
+            Local::File(path) => {
+                let text = fs::read_to_string(path)
+                    .with_context(|| format!("cannot read {}", path.display()))?;
+                (text, path.display().to_string())
+            }
+            Local::Command(command) => {
+                let output = Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .output()
+                    .with_context(|| format!("cannot run {command}"))?;
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    match stderr.lines().last() {
+                        Some(line) => bail!("{command}: {line}"),
+                        None => bail!("{command}: {}", output.status),
+                    }
+                }
+                (
+                    String::from_utf8_lossy(&output.stdout).into_owned(),
+                    command.clone(),
+                )
+            }
+        };
+        parse(&text).with_context(|| format!("{source} is not a weather report"))
+    }
+}
+
+impl WeatherProvider for Local {
+    fn fetch<'a>(&'a self, _client: &'a reqwest::Client) -> Fetch<'a> {
+        Box::pin(async move {
+            // Commands may take a while; keep them off the runtime threads.
+            let local = self.clone();
+            tokio::task::spawn_blocking(move || local.read()).await?
+        })
+    }
+}
+
+fn parse(text: &str) -> Result<Report> {
+    Ok(serde_json::from_str(text)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::Sky;
+
+    #[test]
+    fn parses_a_full_report() {
+        let report = parse(include_str!("fixtures/local.json")).unwrap();
+        assert_eq!(report.current.place, "Home");
+        assert_eq!(report.current.sky, Sky::Fog);
+        assert_eq!(report.current.temperature, 6.5);
+        assert!(!report.current.is_day);
+        assert_eq!(report.hourly.len(), 2);
+        assert_eq!(report.hourly[1].precipitation_chance, 30);
+        assert_eq!(report.daily.len(), 1);
+        assert_eq!(report.daily[0].max, 12.0);
+    }
+
+    #[test]
+    fn fills_in_optional_fields() {
+        let report =
+            parse(r#"{"current": {"sky": "clear", "temperature": 20, "feels_like": 19}}"#).unwrap();
+        assert!(report.current.is_day);
+        assert_eq!(report.current.humidity, 0);
+        assert!(report.hourly.is_empty());
+        assert!(report.daily.is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_skies() {
+        assert!(
+            parse(r#"{"current": {"sky": "hail", "temperature": 0, "feels_like": 0}}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn reads_command_output() {
+        let command = Local::Command(format!(
+            "cat {}/src/weather/fixtures/local.json",
+            env!("CARGO_MANIFEST_DIR")
+        ));
+        assert_eq!(command.read().unwrap().current.place, "Home");
+        let failing = Local::Command("echo offline >&2; exit 1".to_string());
+        assert_eq!(
+            failing.read().unwrap_err().to_string(),
+            "echo offline >&2; exit 1: offline"
+        );
+    }
+}
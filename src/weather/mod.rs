@@ -0,0 +1,472 @@
+//! Current conditions and forecasts, fetched on a background task from a
+//! pluggable [`WeatherProvider`].
+
+mod cache;
+mod local;
+mod open_meteo;
+mod openweathermap;
+mod rule;
+
+use anyhow::{Result, bail};
+use cache::Cache;
+use chrono::{DateTime, Local, NaiveDate};
+pub use rule::{Action, Adjustment, EVALUATE_AHEAD, Rule};
+use serde::{Deserialize, Serialize};
+use std::{env, future::Future, path::PathBuf, pin::Pin, time::Duration};
+use tokio::sync::watch;
+
+/// The `[weather]` section of the config file.
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WeatherConfig {
+    pub enabled: bool,
+    pub provider: ProviderKind,
+    /// Place name, e.g. `"London,GB"`. OpenWeatherMap looks it up, other
+    /// providers only display it.
+    pub location: String,
+    /// Coordinates, required by Open-Meteo and preferred by OpenWeatherMap.
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub units: Units,
+    /// Root of the weather API, replaceable by a local stub for testing.
+    /// Defaults to the provider's public endpoint.
+    pub base_url: Option<String>,
+    /// Overrides `$OPENWEATHER_API_KEY`.
+    pub api_key: Option<String>,
+    /// JSON report for `provider = "file"`.
+    pub file: Option<PathBuf>,
+    /// Shell command printing a JSON report, for `provider = "command"`.
+    pub command: Option<String>,
+    pub refresh_minutes: u32,
+}
+
+impl Default for WeatherConfig {
+    fn default() -> WeatherConfig {
+        WeatherConfig {
+            enabled: true,
+            provider: ProviderKind::OpenWeatherMap,
+            location: "London".to_string(),
+            latitude: None,
+            longitude: None,
+            units: Units::Metric,
+            base_url: None,
+            api_key: None,
+            file: None,
+            command: None,
+            refresh_minutes: 10,
+        }
+    }
+}
+
+impl WeatherConfig {
+    fn refresh_interval(&self) -> Duration {
+        Duration::from_secs(u64::from(self.refresh_minutes.max(1)) * 60)
+    }
+
+    fn coordinates(&self) -> Option<(f64, f64)> {
+        self.latitude.zip(self.longitude)
+    }
+
+    fn base_url(&self, default: &str) -> String {
+        self.base_url
+            .as_deref()
+            .unwrap_or(default)
+            .trim_end_matches('/')
+            .to_string()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProviderKind {
+    #[default]
+    #[serde(rename = "openweathermap")]
+    OpenWeatherMap,
+    OpenMeteo,
+    File,
+    Command,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl Units {
+    pub fn temperature(self) -> &'static str {
+        match self {
+            Units::Metric => "°C",
+            Units::Imperial => "°F",
+        }
+    }
+
+    pub fn speed(self) -> &'static str {
+        match self {
+            Units::Metric => "m/s",
+            Units::Imperial => "mph",
+        }
+    }
+}
+
+/// Broad kind of weather, for icons and scenery.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sky {
+    Clear,
+    Clouds,
+    Drizzle,
+    Rain,
+    Snow,
+    Thunderstorm,
+    Fog,
+}
+
+/// Everything a provider returns. Also the format of the local file and
+/// command providers.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Report {
+    pub current: Conditions,
+    /// Upcoming hours, oldest first. Some providers step by three hours.
+    #[serde(default)]
+    pub hourly: Vec<Hour>,
+    /// Upcoming days starting today.
+    #[serde(default)]
+    pub daily: Vec<Day>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Conditions {
+    /// Place name as reported by the provider.
+    #[serde(default)]
+    pub place: String,
+    pub sky: Sky,
+    /// Provider's wording, e.g. "light rain".
+    #[serde(default)]
+    pub summary: String,
+    pub temperature: f64,
+    pub feels_like: f64,
+    /// Rain or snow in the last hour, in millimetres.
+    #[serde(default)]
+    pub precipitation: f64,
+    /// Relative humidity in percent.
+    #[serde(default)]
+    pub humidity: u8,
+    #[serde(default)]
+    pub wind_speed: f64,
+    /// Direction the wind blows from, in degrees.
+    #[serde(default)]
+    pub wind_direction: u16,
+    #[serde(default = "daytime")]
+    pub is_day: bool,
+    #[serde(default = "Local::now")]
+    pub observed: DateTime<Local>,
+}
+
+fn daytime() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Hour {
+    pub time: DateTime<Local>,
+    pub temperature: f64,
+    pub sky: Sky,
+    /// Chance of precipitation in percent.
+    #[serde(default)]
+    pub precipitation_chance: u8,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Day {
+    pub date: NaiveDate,
+    pub sky: Sky,
+    pub min: f64,
+    pub max: f64,
+    /// Highest chance of precipitation during the day, in percent.
+    #[serde(default)]
+    pub precipitation_chance: u8,
+}
+
+pub type Fetch<'a> = Pin<Box<dyn Future<Output = Result<Report>> + Send + 'a>>;
+
+/// A source of weather data.
+pub trait WeatherProvider: Send + Sync {
+    /// Current conditions with the hourly and daily forecast.
+    fn fetch<'a>(&'a self, client: &'a reqwest::Client) -> Fetch<'a>;
+}
+
+/// Builds the configured provider, or `None` when weather is turned off or
+/// OpenWeatherMap has no API key.
+fn provider(config: &WeatherConfig) -> Result<Option<Box<dyn WeatherProvider>>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    Ok(Some(match config.provider {
+        ProviderKind::OpenWeatherMap => {
+            let api_key = config
+                .api_key
+                .clone()
+                .or_else(|| env::var("OPENWEATHER_API_KEY").ok())
+                .filter(|key| !key.trim().is_empty());
+            let Some(api_key) = api_key else {
+                return Ok(None);
+            };
+            Box::new(openweathermap::OpenWeatherMap::new(config, api_key))
+        }
+        ProviderKind::OpenMeteo => Box::new(open_meteo::OpenMeteo::new(config)?),
+        ProviderKind::File => match &config.file {
+            Some(path) => Box::new(local::Local::File(path.clone())),
+            None => bail!("provider = \"file\" needs a file path"),
+        },
+        ProviderKind::Command => match &config.command {
+            Some(command) => Box::new(local::Local::Command(command.clone())),
+            None => bail!("provider = \"command\" needs a command"),
+        },
+    }))
+}
+
+/// First wait after a failed fetch, doubled on each failure in a row.
+const RETRY_MIN: Duration = Duration::from_secs(30);
+const RETRY_MAX: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Clone, Debug)]
+pub enum Weather {
+    /// Turned off, or no API key.
+    Disabled,
+    Loading,
+    Ready {
+        report: Report,
+        /// Why the report could not be cached for the next start.
+        cache_error: Option<String>,
+    },
+    /// The last good report, from the cache or an earlier fetch, while a
+    /// refresh is pending or failing.
+    Stale {
+        report: Report,
+        fetched: DateTime<Local>,
+        /// Why the last refresh failed; `None` while the first one is
+        /// still on its way.
+        failure: Option<Failure>,
+    },
+    /// Nothing to show yet.
+    Failed(Failure),
+}
+
+#[derive(Clone, Debug)]
+pub struct Failure {
+    pub message: String,
+    /// When the next attempt is due, `None` if there won't be one.
+    pub retry_at: Option<DateTime<Local>>,
+}
+
+impl Weather {
+    /// The report to show, stale or not.
+    pub fn report(&self) -> Option<&Report> {
+        match self {
+            Weather::Ready { report, .. } | Weather::Stale { report, .. } => Some(report),
+            _ => None,
+        }
+    }
+}
+
+/// Compass point for a wind direction in degrees.
+pub fn compass(degrees: u16) -> &'static str {
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    POINTS[((f32::from(degrees % 360) + 22.5) / 45.0) as usize % 8]
+}
+
+/// Starts fetching the weather every `refresh_minutes`, backing off when
+/// fetches fail. The last good report is cached on disk and shown at once
+/// on the next start. The task stops once the receiver is dropped.
+pub fn spawn(config: WeatherConfig) -> watch::Receiver<Weather> {
+    let provider = match provider(&config) {
+        Ok(Some(provider)) => provider,
+        Ok(None) => return watch::channel(Weather::Disabled).1,
+        Err(err) => {
+            let failure = Failure {
+                message: format!("{err:#}"),
+                retry_at: None,
+            };
+            return watch::channel(Weather::Failed(failure)).1;
+        }
+    };
+
+    let cache = Cache::open_default(&config);
+    let mut last = cache.as_ref().and_then(Cache::load);
+    let refresh = config.refresh_interval();
+    // A report younger than the refresh interval is as good as a new one.
+    let (initial, mut wait) = match &last {
+        Some((fetched, report)) => {
+            let age = (Local::now() - *fetched).to_std().unwrap_or_default();
+            match refresh.checked_sub(age) {
+                Some(remaining) => {
+                    let ready = Weather::Ready {
+                        report: report.clone(),
+                        cache_error: None,
+                    };
+                    (ready, remaining)
+                }
+                None => {
+                    let stale = Weather::Stale {
+                        report: report.clone(),
+                        fetched: *fetched,
+                        failure: None,
+                    };
+                    (stale, Duration::ZERO)
+                }
+            }
+        }
+        None => (Weather::Loading, Duration::ZERO),
+    };
+
+    let (tx, rx) = watch::channel(initial);
+    tokio::spawn(async move {
+        let client = match reqwest::Client::builder()
+            .timeout(Duration::from_secs(15))
+            .user_agent(concat!("clockradio/", env!("CARGO_PKG_VERSION")))
+            .build()
+        {
+            Ok(client) => client,
+            Err(err) => {
+                tx.send_replace(Weather::Failed(Failure {
+                    message: err.to_string(),
+                    retry_at: None,
+                }));
+                return;
+            }
+        };
+        let mut failures = 0;
+        loop {
+            tokio::time::sleep(wait).await;
+            let weather = match provider.fetch(&client).await {
+                Ok(report) => {
+                    failures = 0;
+                    wait = refresh;
+                    let fetched = Local::now();
+                    let cache_error = cache
+                        .as_ref()
+                        .and_then(|cache| cache.save(fetched, &report).err())
+                        .map(|err| format!("{err:#}"));
+                    last = Some((fetched, report.clone()));
+                    Weather::Ready {
+                        report,
+                        cache_error,
+                    }
+                }
+                Err(err) => {
+                    failures += 1;
+                    wait = retry_delay(failures);
+                    let failure = Failure {
+                        message: format!("{err:#}"),
+                        retry_at: Some(Local::now() + wait),
+                    };
+                    match &last {
+                        Some((fetched, report)) => Weather::Stale {
+                            report: report.clone(),
+                            fetched: *fetched,
+                            failure: Some(failure),
+                        },
+                        None => Weather::Failed(failure),
+                    }
+                }
+            };
+            if tx.send(weather).is_err() {
+                return;
+            }
+        }
+    });
+    rx
+}
+
+/// How long to wait after `failures` failed fetches in a row.
+fn retry_delay(failures: u32) -> Duration {
+    RETRY_MIN
+        .saturating_mul(1 << failures.saturating_sub(1).min(16))
+        .min(RETRY_MAX)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    #[test]
+    fn doubles_the_retry_delay_up_to_the_limit() {
+        let delays: Vec<u64> = [1, 2, 3, 6, 7, 100, u32::MAX]
+            .into_iter()
+            .map(|failures| retry_delay(failures).as_secs())
+            .collect();
+        assert_eq!(delays, [30, 60, 120, 960, 1800, 1800, 1800]);
+        assert_eq!(retry_delay(0), RETRY_MIN);
+    }
+
+    /// A report of `sky` and `temperature` now, with the given hours
+    /// forecast.
+    pub fn report(sky: Sky, temperature: f64, hours: &[(DateTime<Local>, Sky, f64)]) -> Report {
+        Report {
+            current: Conditions {
+                place: String::new(),
+                sky,
+                summary: String::new(),
+                temperature,
+                feels_like: temperature,
+                precipitation: 0.0,
+                humidity: 0,
+                wind_speed: 0.0,
+                wind_direction: 0,
+                is_day: false,
+                observed: Local::now(),
+            },
+            hourly: hours
+                .iter()
+                .map(|&(time, sky, temperature)| Hour {
+                    time,
+                    temperature,
+                    sky,
+                    precipitation_chance: 0,
+                })
+                .collect(),
+            daily: Vec::new(),
+        }
+    }
+
+    /// A stand-in for a weather API on a local port. `respond` maps each
+    /// request target, path and query, to a status and JSON body. Returns
+    /// the base URL and the targets requested so far.
+    pub fn serve(respond: fn(&str) -> (u16, &'static str)) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let log = requests.clone();
+        thread::spawn(move || {
+            for socket in listener.incoming() {
+                let Ok(mut socket) = socket else { return };
+                let mut reader = BufReader::new(&socket);
+                let mut request_line = String::new();
+                let _ = reader.read_line(&mut request_line);
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                    line.clear();
+                }
+                let target = request_line.split(' ').nth(1).unwrap_or("").to_string();
+                let (status, body) = respond(&target);
+                log.lock().unwrap().push(target);
+                let _ = write!(
+                    socket,
+                    "HTTP/1.1 {status} Status\r\ncontent-type: application/json\r\n\
+                     content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+        (base_url, requests)
+    }
+}
@@ -0,0 +1,363 @@
+//! Open-Meteo forecast API, `/v1/forecast`. Free and keyless, but only
+//! takes coordinates.
+
+use super::{Conditions, Day, Fetch, Hour, Report, Sky, Units, WeatherConfig, WeatherProvider};
+use anyhow::{Result, bail};
+use chrono::{DateTime, FixedOffset, Local, TimeZone};
+use serde::Deserialize;
+
+const BASE_URL: &str = "https://api.open-meteo.com";
+
+const CURRENT: &str = "temperature_2m,apparent_temperature,relative_humidity_2m,is_day,\
+                       precipitation,weather_code,wind_speed_10m,wind_direction_10m";
+const HOURLY: &str = "temperature_2m,weather_code,precipitation_probability";
+const DAILY: &str =
+    "weather_code,temperature_2m_min,temperature_2m_max,precipitation_probability_max";
+
+pub struct OpenMeteo {
+    base_url: String,
+    place: String,
+    query: Vec<(&'static str, String)>,
+}
+
+impl OpenMeteo {
+    pub fn new(config: &WeatherConfig) -> Result<OpenMeteo> {
+        let Some((lat, lon)) = config.coordinates() else {
+            bail!("Open-Meteo needs a latitude and longitude");
+        };
+        let (temperature, speed) = match config.units {
+            Units::Metric => ("celsius", "ms"),
+            Units::Imperial => ("fahrenheit", "mph"),
+        };
+        let query = vec![
+            ("latitude", lat.to_string()),
+            ("longitude", lon.to_string()),
+            ("current", CURRENT.to_string()),
+            ("hourly", HOURLY.to_string()),
+            ("daily", DAILY.to_string()),
+            ("temperature_unit", temperature.to_string()),
+            ("wind_speed_unit", speed.to_string()),
+            ("timezone", "auto".to_string()),
+            ("timeformat", "unixtime".to_string()),
+            ("forecast_days", "7".to_string()),
+        ];
+        Ok(OpenMeteo {
+            base_url: config.base_url(BASE_URL),
+            place: config.location.clone(),
+            query,
+        })
+    }
+}
+
+impl WeatherProvider for OpenMeteo {
+    fn fetch<'a>(&'a self, client: &'a reqwest::Client) -> Fetch<'a> {
+        Box::pin(async move {
+            let response = client
+                .get(format!("{}/v1/forecast", self.base_url))
+                .query(&self.query)
+                .send()
+                .await?;
+            let status = response.status();
+            if !status.is_success() {
+                let message = match response.json::<ApiError>().await {
+                    Ok(error) => error.reason,
+                    Err(_) => status.to_string(),
+                };
+                bail!("Open-Meteo: {message}");
+            }
+            Ok(report(response.json().await?, &self.place))
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Response {
+    utc_offset_seconds: i32,
+    current: Current,
+    hourly: Hourly,
+    daily: Daily,
+}
+
+#[derive(Deserialize)]
+struct Current {
+    time: i64,
+    temperature_2m: f64,
+    apparent_temperature: f64,
+    relative_humidity_2m: u8,
+    is_day: u8,
+    precipitation: f64,
+    weather_code: u8,
+    wind_speed_10m: f64,
+    wind_direction_10m: u16,
+}
+
+/// Columns of the hourly forecast, starting at midnight today.
+#[derive(Deserialize)]
+struct Hourly {
+    time: Vec<i64>,
+    temperature_2m: Vec<f64>,
+    weather_code: Vec<u8>,
+    /// Missing for some models.
+    precipitation_probability: Vec<Option<u8>>,
+}
+
+/// Columns of the daily forecast; times are local midnights.
+#[derive(Deserialize)]
+struct Daily {
+    time: Vec<i64>,
+    weather_code: Vec<u8>,
+    temperature_2m_min: Vec<f64>,
+    temperature_2m_max: Vec<f64>,
+    precipitation_probability_max: Vec<Option<u8>>,
+}
+
+/// Error body, e.g. `{"error": true, "reason": "Latitude must be ..."}`.
+#[derive(Deserialize)]
+struct ApiError {
+    reason: String,
+}
+
+fn report(response: Response, place: &str) -> Report {
+    let offset = FixedOffset::east_opt(response.utc_offset_seconds)
+        .unwrap_or(FixedOffset::east_opt(0).unwrap());
+    let current = &response.current;
+
+    // The hourly columns start at midnight; keep the current hour onwards.
+    let hourly = &response.hourly;
+    let hourly = (0..hourly.time.len())
+        .filter(|&i| hourly.time[i] + 3600 > current.time)
+        .filter_map(|i| {
+            Some(Hour {
+                time: local_time(hourly.time[i]),
+                temperature: *hourly.temperature_2m.get(i)?,
+                sky: sky(*hourly.weather_code.get(i)?),
+                precipitation_chance: hourly
+                    .precipitation_probability
+                    .get(i)
+                    .copied()
+                    .flatten()
+                    .unwrap_or(0),
+            })
+        })
+        .collect();
+
+    let daily = &response.daily;
+    let daily = (0..daily.time.len())
+        .filter_map(|i| {
+            Some(Day {
+                date: offset
+                    .timestamp_opt(daily.time[i], 0)
+                    .single()?
+                    .date_naive(),
+                sky: sky(*daily.weather_code.get(i)?),
+                min: *daily.temperature_2m_min.get(i)?,
+                max: *daily.temperature_2m_max.get(i)?,
+                precipitation_chance: daily
+                    .precipitation_probability_max
+                    .get(i)
+                    .copied()
+                    .flatten()
+                    .unwrap_or(0),
+            })
+        })
+        .collect();
+
+    Report {
+        current: Conditions {
+            place: place.to_string(),
+            sky: sky(current.weather_code),
+            summary: summary(current.weather_code).to_string(),
+            temperature: current.temperature_2m,
+            feels_like: current.apparent_temperature,
+            precipitation: current.precipitation,
+            humidity: current.relative_humidity_2m,
+            wind_speed: current.wind_speed_10m,
+            wind_direction: current.wind_direction_10m,
+            is_day: current.is_day != 0,
+            observed: local_time(current.time),
+        },
+        hourly,
+        daily,
+    }
+}
+
+fn local_time(timestamp: i64) -> DateTime<Local> {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .unwrap_or_else(Local::now)
+}
+
+/// Maps a WMO weather interpretation code.
+fn sky(code: u8) -> Sky {
+    match code {
+        0 | 1 => Sky::Clear,
+        45 | 48 => Sky::Fog,
+        51..=57 => Sky::Drizzle,
+        61..=67 | 80..=82 => Sky::Rain,
+        71..=77 | 85 | 86 => Sky::Snow,
+        95..=99 => Sky::Thunderstorm,
+        _ => Sky::Clouds,
+    }
+}
+
+/// Wording for a WMO code, in the style of OpenWeatherMap's descriptions.
+fn summary(code: u8) -> &'static str {
+    match code {
+        0 => "clear sky",
+        1 => "mainly clear",
+        2 => "partly cloudy",
+        3 => "overcast",
+        45 => "fog",
+        48 => "freezing fog",
+        51 => "light drizzle",
+        53 => "drizzle",
+        55 => "heavy drizzle",
+        56 | 57 => "freezing drizzle",
+        61 => "light rain",
+        63 => "rain",
+        65 => "heavy rain",
+        66 | 67 => "freezing rain",
+        71 => "light snow",
+        73 => "snow",
+        75 => "heavy snow",
+        77 => "snow grains",
+        80 => "light showers",
+        81 => "showers",
+        82 => "violent showers",
+        85 | 86 => "snow showers",
+        95 => "thunderstorm",
+        96..=99 => "thunderstorm with hail",
+        _ => "cloudy",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::tests::serve;
+    use chrono::NaiveDate;
+
+    fn fixture() -> Report {
+        let response = serde_json::from_str(include_str!("fixtures/open_meteo.json")).unwrap();
+        report(response, "Berlin")
+    }
+
+    #[test]
+    fn parses_current_conditions() {
+        let current = fixture().current;
+        assert_eq!(current.place, "Berlin");
+        assert_eq!(current.sky, Sky::Snow);
+        assert_eq!(current.summary, "light snow");
+        assert_eq!(current.temperature, -1.4);
+        assert_eq!(current.feels_like, -5.2);
+        assert_eq!(current.precipitation, 0.3);
+        assert_eq!(current.humidity, 93);
+        assert_eq!(current.wind_speed, 3.9);
+        assert_eq!(current.wind_direction, 75);
+        assert!(!current.is_day);
+        assert_eq!(current.observed.timestamp(), 1_760_502_600);
+    }
+
+    #[test]
+    fn keeps_hours_from_the_current_one() {
+        let hourly = fixture().hourly;
+        assert_eq!(hourly.len(), 4);
+        assert_eq!(hourly[0].time.timestamp(), 1_760_500_800);
+        assert_eq!(hourly[0].sky, Sky::Snow);
+        assert_eq!(hourly[0].precipitation_chance, 60);
+        assert_eq!(hourly[2].sky, Sky::Fog);
+        // A null probability counts as none.
+        assert_eq!(hourly[3].precipitation_chance, 0);
+    }
+
+    #[test]
+    fn parses_daily_forecast_in_local_dates() {
+        let daily = fixture().daily;
+        assert_eq!(daily.len(), 2);
+        assert_eq!(
+            daily[0].date,
+            NaiveDate::from_ymd_opt(2025, 10, 15).unwrap()
+        );
+        assert_eq!(daily[0].min, -2.1);
+        assert_eq!(daily[0].max, 4.3);
+        assert_eq!(daily[0].sky, Sky::Snow);
+        assert_eq!(daily[0].precipitation_chance, 70);
+        assert_eq!(daily[1].sky, Sky::Thunderstorm);
+        assert_eq!(daily[1].precipitation_chance, 0);
+    }
+
+    #[test]
+    fn maps_wmo_codes() {
+        assert_eq!(sky(0), Sky::Clear);
+        assert_eq!(sky(3), Sky::Clouds);
+        assert_eq!(sky(48), Sky::Fog);
+        assert_eq!(sky(55), Sky::Drizzle);
+        assert_eq!(sky(81), Sky::Rain);
+        assert_eq!(sky(86), Sky::Snow);
+        assert_eq!(sky(99), Sky::Thunderstorm);
+    }
+
+    fn berlin(base_url: String) -> WeatherConfig {
+        WeatherConfig {
+            location: "Berlin".to_string(),
+            latitude: Some(52.52),
+            longitude: Some(13.41),
+            base_url: Some(base_url),
+            ..WeatherConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn fetches_the_forecast() {
+        let (base_url, requests) = serve(|target| match target.split('?').next() {
+            Some("/v1/forecast") => (200, include_str!("fixtures/open_meteo.json")),
+            _ => (404, r#"{"error": true, "reason": "Not Found"}"#),
+        });
+        let provider = OpenMeteo::new(&berlin(base_url)).unwrap();
+        let report = provider.fetch(&reqwest::Client::new()).await.unwrap();
+        assert_eq!(report.current.place, "Berlin");
+        assert_eq!(report.current.sky, Sky::Snow);
+        assert_eq!(report.hourly.len(), 4);
+        assert_eq!(report.daily.len(), 2);
+
+        let requests = requests.lock().unwrap();
+        let query = requests[0].strip_prefix("/v1/forecast?").unwrap();
+        let query: Vec<&str> = query.split('&').collect();
+        assert_eq!(query[..2], ["latitude=52.52", "longitude=13.41"]);
+        assert!(query.contains(&"temperature_unit=celsius"));
+        assert!(query.contains(&"wind_speed_unit=ms"));
+        assert!(query.contains(&"timeformat=unixtime"));
+        assert!(
+            query.contains(&"hourly=temperature_2m%2Cweather_code%2Cprecipitation_probability")
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_api_errors() {
+        let (base_url, _) = serve(|_| {
+            (
+                400,
+                r#"{"error": true, "reason": "Latitude must be in range of -90 to 90°. Given: 152.52."}"#,
+            )
+        });
+        let provider = OpenMeteo::new(&berlin(base_url)).unwrap();
+        let error = provider.fetch(&reqwest::Client::new()).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Open-Meteo: Latitude must be in range of -90 to 90°. Given: 152.52."
+        );
+    }
+
+    #[test]
+    fn needs_coordinates() {
+        let config = WeatherConfig {
+            latitude: None,
+            ..berlin(String::new())
+        };
+        assert_eq!(
+            OpenMeteo::new(&config).err().unwrap().to_string(),
+            "Open-Meteo needs a latitude and longitude"
+        );
+    }
+}
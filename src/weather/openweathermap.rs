@@ -0,0 +1,418 @@
+//! OpenWeatherMap current weather and 5 day / 3 hour forecast APIs,
+//! `/data/2.5/weather` and `/data/2.5/forecast`.
+
+use super::{Conditions, Day, Fetch, Hour, Report, Sky, Units, WeatherConfig, WeatherProvider};
+use anyhow::{Result, bail};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, de::DeserializeOwned};
+
+const BASE_URL: &str = "https://api.openweathermap.org";
+
+pub struct OpenWeatherMap {
+    base_url: String,
+    query: Vec<(&'static str, String)>,
+}
+
+impl OpenWeatherMap {
+    pub fn new(config: &WeatherConfig, api_key: String) -> OpenWeatherMap {
+        let mut query = match config.coordinates() {
+            Some((lat, lon)) => vec![("lat", lat.to_string()), ("lon", lon.to_string())],
+            None => vec![("q", config.location.clone())],
+        };
+        let units = match config.units {
+            Units::Metric => "metric",
+            Units::Imperial => "imperial",
+        };
+        query.push(("units", units.to_string()));
+        query.push(("appid", api_key));
+        OpenWeatherMap {
+            base_url: config.base_url(BASE_URL),
+            query,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, client: &reqwest::Client, path: &str) -> Result<T> {
+        let response = client
+            .get(format!("{}{path}", self.base_url))
+            .query(&self.query)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = match response.json::<ApiError>().await {
+                Ok(error) => error.message,
+                Err(_) => status.to_string(),
+            };
+            bail!("OpenWeatherMap: {message}");
+        }
+        Ok(response.json().await?)
+    }
+}
+
+impl WeatherProvider for OpenWeatherMap {
+    fn fetch<'a>(&'a self, client: &'a reqwest::Client) -> Fetch<'a> {
+        Box::pin(async move {
+            let current = self.get(client, "/data/2.5/weather").await?;
+            let forecast = self.get(client, "/data/2.5/forecast").await?;
+            Ok(report(current, forecast))
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Current {
+    name: String,
+    weather: Vec<Description>,
+    main: Main,
+    wind: Wind,
+    dt: i64,
+    sys: Sys,
+    rain: Option<Precipitation>,
+    snow: Option<Precipitation>,
+}
+
+#[derive(Deserialize)]
+struct Description {
+    id: u16,
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Deserialize)]
+struct Main {
+    temp: f64,
+    #[serde(default)]
+    feels_like: f64,
+    #[serde(default)]
+    temp_min: f64,
+    #[serde(default)]
+    temp_max: f64,
+    #[serde(default)]
+    humidity: u8,
+}
+
+#[derive(Deserialize)]
+struct Wind {
+    speed: f64,
+    #[serde(default)]
+    deg: u16,
+}
+
+/// Precipitation in millimetres.
+#[derive(Deserialize)]
+struct Precipitation {
+    #[serde(rename = "1h", default)]
+    one_hour: f64,
+}
+
+#[derive(Deserialize)]
+struct Sys {
+    #[serde(default)]
+    sunrise: i64,
+    #[serde(default)]
+    sunset: i64,
+}
+
+#[derive(Deserialize)]
+struct Forecast {
+    list: Vec<Slot>,
+    city: City,
+}
+
+/// Three hours of forecast.
+#[derive(Deserialize)]
+struct Slot {
+    dt: i64,
+    main: Main,
+    weather: Vec<Description>,
+    /// Probability of precipitation, 0.0 to 1.0.
+    #[serde(default)]
+    pop: f64,
+}
+
+#[derive(Deserialize)]
+struct City {
+    /// Offset from UTC in seconds.
+    #[serde(default)]
+    timezone: i32,
+}
+
+/// Error body, e.g. `{"cod": 401, "message": "Invalid API key"}`.
+#[derive(Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+fn report(current: Current, forecast: Forecast) -> Report {
+    let offset =
+        FixedOffset::east_opt(forecast.city.timezone).unwrap_or(FixedOffset::east_opt(0).unwrap());
+    let hourly: Vec<Hour> = forecast
+        .list
+        .iter()
+        .map(|slot| Hour {
+            time: local_time(slot.dt),
+            temperature: slot.main.temp,
+            sky: sky(&slot.weather),
+            precipitation_chance: percent(slot.pop),
+        })
+        .collect();
+
+    // The forecast only comes in three hour slots; a day is the slots that
+    // fall on its date at the forecast location.
+    let mut daily: Vec<Day> = Vec::new();
+    for slot in &forecast.list {
+        let date = location_date(slot.dt, offset);
+        let sky = sky(&slot.weather);
+        let chance = percent(slot.pop);
+        match daily.last_mut() {
+            Some(day) if day.date == date => {
+                day.min = day.min.min(slot.main.temp_min);
+                day.max = day.max.max(slot.main.temp_max);
+                day.precipitation_chance = day.precipitation_chance.max(chance);
+                if severity(sky) > severity(day.sky) {
+                    day.sky = sky;
+                }
+            }
+            _ => daily.push(Day {
+                date,
+                sky,
+                min: slot.main.temp_min,
+                max: slot.main.temp_max,
+                precipitation_chance: chance,
+            }),
+        }
+    }
+
+    let description = current.weather.first();
+    Report {
+        current: Conditions {
+            place: current.name,
+            sky: sky(&current.weather),
+            summary: description
+                .map(|d| d.description.clone())
+                .unwrap_or_default(),
+            temperature: current.main.temp,
+            feels_like: current.main.feels_like,
+            precipitation: [current.rain, current.snow]
+                .iter()
+                .flatten()
+                .map(|amount| amount.one_hour)
+                .sum(),
+            humidity: current.main.humidity,
+            wind_speed: current.wind.speed,
+            wind_direction: current.wind.deg,
+            is_day: current.sys.sunrise == current.sys.sunset
+                || (current.sys.sunrise..current.sys.sunset).contains(&current.dt),
+            observed: local_time(current.dt),
+        },
+        hourly,
+        daily,
+    }
+}
+
+fn local_time(timestamp: i64) -> DateTime<Local> {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .unwrap_or_else(Local::now)
+}
+
+fn location_date(timestamp: i64, offset: FixedOffset) -> NaiveDate {
+    offset
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.date_naive())
+        .unwrap_or_default()
+}
+
+fn percent(probability: f64) -> u8 {
+    (probability * 100.0).round().clamp(0.0, 100.0) as u8
+}
+
+/// Maps a weather condition id, see
+/// <https://openweathermap.org/weather-conditions>.
+fn sky(weather: &[Description]) -> Sky {
+    match weather.first().map_or(800, |description| description.id) {
+        200..=299 => Sky::Thunderstorm,
+        300..=399 => Sky::Drizzle,
+        500..=599 => Sky::Rain,
+        600..=699 => Sky::Snow,
+        700..=799 => Sky::Fog,
+        800 => Sky::Clear,
+        _ => Sky::Clouds,
+    }
+}
+
+/// Which sky describes a day that has several.
+fn severity(sky: Sky) -> u8 {
+    match sky {
+        Sky::Clear => 0,
+        Sky::Clouds => 1,
+        Sky::Fog => 2,
+        Sky::Drizzle => 3,
+        Sky::Rain => 4,
+        Sky::Snow => 5,
+        Sky::Thunderstorm => 6,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::tests::serve;
+
+    fn fixture() -> Report {
+        let current =
+            serde_json::from_str(include_str!("fixtures/openweathermap_current.json")).unwrap();
+        let forecast =
+            serde_json::from_str(include_str!("fixtures/openweathermap_forecast.json")).unwrap();
+        report(current, forecast)
+    }
+
+    #[test]
+    fn parses_current_conditions() {
+        let current = fixture().current;
+        assert_eq!(current.place, "London");
+        assert_eq!(current.sky, Sky::Rain);
+        assert_eq!(current.summary, "light rain");
+        assert_eq!(current.temperature, 11.8);
+        assert_eq!(current.feels_like, 11.1);
+        assert_eq!(current.precipitation, 0.41);
+        assert_eq!(current.humidity, 87);
+        assert_eq!(current.wind_speed, 4.6);
+        assert_eq!(current.wind_direction, 230);
+        assert!(current.is_day);
+        assert_eq!(current.observed.timestamp(), 1_760_518_800);
+    }
+
+    #[test]
+    fn parses_three_hourly_forecast() {
+        let hourly = fixture().hourly;
+        assert_eq!(hourly.len(), 6);
+        assert_eq!(hourly[0].time.timestamp(), 1_760_522_400);
+        assert_eq!(hourly[0].temperature, 12.4);
+        assert_eq!(hourly[0].sky, Sky::Rain);
+        assert_eq!(hourly[0].precipitation_chance, 78);
+        assert_eq!(hourly[3].sky, Sky::Clear);
+        assert_eq!(hourly[3].precipitation_chance, 0);
+    }
+
+    #[test]
+    fn groups_slots_into_days_at_the_location() {
+        let daily = fixture().daily;
+        assert_eq!(daily.len(), 2);
+        assert_eq!(
+            daily[0].date,
+            NaiveDate::from_ymd_opt(2025, 10, 15).unwrap()
+        );
+        assert_eq!(daily[0].min, 9.6);
+        assert_eq!(daily[0].max, 13.2);
+        assert_eq!(daily[0].sky, Sky::Rain);
+        assert_eq!(daily[0].precipitation_chance, 78);
+        assert_eq!(
+            daily[1].date,
+            NaiveDate::from_ymd_opt(2025, 10, 16).unwrap()
+        );
+        assert_eq!(daily[1].sky, Sky::Snow);
+    }
+
+    #[test]
+    fn maps_condition_ids() {
+        let sky_of = |id| {
+            sky(&[Description {
+                id,
+                description: String::new(),
+            }])
+        };
+        assert_eq!(sky_of(211), Sky::Thunderstorm);
+        assert_eq!(sky_of(301), Sky::Drizzle);
+        assert_eq!(sky_of(601), Sky::Snow);
+        assert_eq!(sky_of(741), Sky::Fog);
+        assert_eq!(sky_of(800), Sky::Clear);
+        assert_eq!(sky_of(804), Sky::Clouds);
+        assert_eq!(sky(&[]), Sky::Clear);
+    }
+
+    fn respond(target: &str) -> (u16, &'static str) {
+        match target.split('?').next() {
+            Some("/data/2.5/weather") => {
+                (200, include_str!("fixtures/openweathermap_current.json"))
+            }
+            Some("/data/2.5/forecast") => {
+                (200, include_str!("fixtures/openweathermap_forecast.json"))
+            }
+            _ => (404, r#"{"cod": "404", "message": "Internal error"}"#),
+        }
+    }
+
+    #[tokio::test]
+    async fn fetches_both_endpoints() {
+        let (base_url, requests) = serve(respond);
+        let config = WeatherConfig {
+            base_url: Some(format!("{base_url}/")),
+            latitude: Some(51.51),
+            longitude: Some(-0.13),
+            ..WeatherConfig::default()
+        };
+        let provider = OpenWeatherMap::new(&config, "secret".to_string());
+        let report = provider.fetch(&reqwest::Client::new()).await.unwrap();
+        assert_eq!(report.current.place, "London");
+        assert_eq!(report.hourly.len(), 6);
+        assert_eq!(report.daily.len(), 2);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            [
+                "/data/2.5/weather?lat=51.51&lon=-0.13&units=metric&appid=secret",
+                "/data/2.5/forecast?lat=51.51&lon=-0.13&units=metric&appid=secret",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn looks_up_places_by_name() {
+        let (base_url, requests) = serve(respond);
+        let config = WeatherConfig {
+            base_url: Some(base_url),
+            location: "London,GB".to_string(),
+            units: Units::Imperial,
+            ..WeatherConfig::default()
+        };
+        let provider = OpenWeatherMap::new(&config, "secret".to_string());
+        provider.fetch(&reqwest::Client::new()).await.unwrap();
+        assert_eq!(
+            requests.lock().unwrap()[0],
+            "/data/2.5/weather?q=London%2CGB&units=imperial&appid=secret"
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_api_errors() {
+        let (base_url, _) = serve(|_| {
+            (
+                401,
+                r#"{"cod": 401, "message": "Invalid API key. Please see https://openweathermap.org/faq#error401 for more info."}"#,
+            )
+        });
+        let config = WeatherConfig {
+            base_url: Some(base_url),
+            ..WeatherConfig::default()
+        };
+        let provider = OpenWeatherMap::new(&config, "wrong".to_string());
+        let error = provider.fetch(&reqwest::Client::new()).await.unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("OpenWeatherMap: Invalid API key."),
+            "{error}"
+        );
+
+        let (base_url, _) = serve(|_| (502, "<html>Bad Gateway</html>"));
+        let config = WeatherConfig {
+            base_url: Some(base_url),
+            ..WeatherConfig::default()
+        };
+        let provider = OpenWeatherMap::new(&config, "secret".to_string());
+        let error = provider.fetch(&reqwest::Client::new()).await.unwrap_err();
+        assert_eq!(error.to_string(), "OpenWeatherMap: 502 Bad Gateway");
+    }
+}
@@ -0,0 +1,323 @@
+use super::{Report, Sky, Units};
+use chrono::{DateTime, Local, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How long before an alarm its rule starts watching the forecast.
+pub const EVALUATE_AHEAD: TimeDelta = TimeDelta::hours(1);
+/// Weather in the hours before the alarm counts too: snow overnight still
+/// needs shovelling.
+const LOOKBACK: TimeDelta = TimeDelta::hours(3);
+
+/// Changes an alarm when the forecast calls for it, as in `20m earlier if
+/// snow or freezing` or `skip if rain`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    pub action: Action,
+    /// Any one of them triggers the action.
+    pub conditions: Vec<Condition>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    /// Ring this many minutes earlier.
+    Earlier(u32),
+    Skip,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Condition {
+    /// Drizzle, rain or a thunderstorm.
+    Rain,
+    Snow,
+    /// At or below 0°C (32°F).
+    Freezing,
+    Storm,
+}
+
+impl Condition {
+    fn as_str(self) -> &'static str {
+        match self {
+            Condition::Rain => "rain",
+            Condition::Snow => "snow",
+            Condition::Freezing => "freezing",
+            Condition::Storm => "storm",
+        }
+    }
+}
+
+/// What a rule decided for an alarm's upcoming ring.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Adjustment {
+    pub action: Action,
+    /// What in the forecast triggered it, e.g. `snow forecast`.
+    pub reason: String,
+}
+
+impl Rule {
+    /// Parses the editor syntax. Empty means no rule.
+    pub fn parse(input: &str) -> Result<Option<Rule>, String> {
+        let input = input.trim().to_lowercase();
+        if input.is_empty() || input == "none" {
+            return Ok(None);
+        }
+        let (action, conditions) = input
+            .split_once(" if ")
+            .ok_or("Weather rule needs \"if\", as in \"skip if rain\"")?;
+
+        // "wake 20 minutes earlier" reads as well as "20m earlier".
+        let words: Vec<&str> = action
+            .split_whitespace()
+            .filter(|word| !matches!(*word, "wake" | "ring" | "minutes" | "minute" | "mins"))
+            .collect();
+        let action = match words.as_slice() {
+            ["skip"] => Action::Skip,
+            [minutes, "earlier"] | ["earlier", minutes] => {
+                let minutes = minutes
+                    .trim_end_matches("min")
+                    .trim_end_matches('m')
+                    .parse()
+                    .ok()
+                    .filter(|minutes| (1..=180).contains(minutes))
+                    .ok_or("Wake 1 to 180 minutes earlier, as in \"20m earlier\"")?;
+                Action::Earlier(minutes)
+            }
+            _ => return Err(format!("Unknown weather action \"{action}\"")),
+        };
+
+        let mut parsed = Vec::new();
+        for word in conditions.split(|c: char| c == ',' || c.is_whitespace()) {
+            let condition = match word {
+                "" | "or" | "is" | "it's" | "temperature" | "temperatures" | "forecast" => continue,
+                "rain" | "raining" | "rainy" | "wet" => Condition::Rain,
+                "snow" | "snowing" | "snowy" => Condition::Snow,
+                "freezing" | "frost" | "ice" | "icy" => Condition::Freezing,
+                "storm" | "stormy" | "thunder" | "thunderstorm" => Condition::Storm,
+                _ => return Err(format!("Unknown weather \"{word}\"")),
+            };
+            if !parsed.contains(&condition) {
+                parsed.push(condition);
+            }
+        }
+        if parsed.is_empty() {
+            return Err("Say which weather, as in \"skip if rain\"".to_string());
+        }
+        Ok(Some(Rule {
+            action,
+            conditions: parsed,
+        }))
+    }
+
+    /// Checks the forecast for the hours up to an alarm due at `due`.
+    /// `None` when the alarm should ring as planned.
+    pub fn evaluate(
+        &self,
+        report: &Report,
+        due: DateTime<Local>,
+        units: Units,
+    ) -> Option<Adjustment> {
+        let hours: Vec<(Sky, f64)> = report
+            .hourly
+            .iter()
+            .filter(|hour| hour.time <= due && hour.time > due - LOOKBACK)
+            .map(|hour| (hour.sky, hour.temperature))
+            .collect();
+        // Without an hourly forecast, go by what it is like now.
+        let hours = if hours.is_empty() {
+            vec![(report.current.sky, report.current.temperature)]
+        } else {
+            hours
+        };
+
+        let freezing = match units {
+            Units::Metric => 0.0,
+            Units::Imperial => 32.0,
+        };
+        let reason = self
+            .conditions
+            .iter()
+            .find_map(|condition| match condition {
+                Condition::Rain => hours
+                    .iter()
+                    .any(|(sky, _)| matches!(sky, Sky::Drizzle | Sky::Rain | Sky::Thunderstorm))
+                    .then(|| "rain forecast".to_string()),
+                Condition::Snow => hours
+                    .iter()
+                    .any(|(sky, _)| *sky == Sky::Snow)
+                    .then(|| "snow forecast".to_string()),
+                Condition::Freezing => hours
+                    .iter()
+                    .map(|(_, temperature)| *temperature)
+                    .reduce(f64::min)
+                    .filter(|coldest| *coldest <= freezing)
+                    .map(|coldest| format!("{coldest:.0}{} forecast", units.temperature())),
+                Condition::Storm => hours
+                    .iter()
+                    .any(|(sky, _)| *sky == Sky::Thunderstorm)
+                    .then(|| "storm forecast".to_string()),
+            })?;
+        Some(Adjustment {
+            action: self.action,
+            reason,
+        })
+    }
+
+    /// How far ahead of its time the rule may move the alarm.
+    pub fn lead(&self) -> TimeDelta {
+        match self.action {
+            Action::Earlier(minutes) => TimeDelta::minutes(i64::from(minutes)),
+            Action::Skip => TimeDelta::zero(),
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.action {
+            Action::Earlier(minutes) => write!(f, "{minutes}m earlier if ")?,
+            Action::Skip => write!(f, "skip if ")?,
+        }
+        let conditions: Vec<&str> = self.conditions.iter().map(|c| c.as_str()).collect();
+        write!(f, "{}", conditions.join(" or "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::tests;
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 16, hour, 0, 0).unwrap()
+    }
+
+    /// Clear and mild now, with the given hours forecast.
+    fn report(hours: &[(u32, Sky, f64)]) -> Report {
+        let hours: Vec<_> = hours
+            .iter()
+            .map(|&(hour, sky, temperature)| (at(hour), sky, temperature))
+            .collect();
+        tests::report(Sky::Clear, 8.0, &hours)
+    }
+
+    fn rule(input: &str) -> Rule {
+        Rule::parse(input).unwrap().unwrap()
+    }
+
+    #[test]
+    fn parses_the_editor_syntax() {
+        let expected = Rule {
+            action: Action::Earlier(20),
+            conditions: vec![Condition::Snow, Condition::Freezing],
+        };
+        assert_eq!(
+            rule("wake 20 minutes earlier if snow or freezing temperature is forecast"),
+            expected
+        );
+        assert_eq!(rule("20m earlier if snow, frost"), expected);
+        assert_eq!(rule("Earlier 20min if SNOW or snowy or ice"), expected);
+        assert_eq!(
+            rule("skip if rain or thunder"),
+            Rule {
+                action: Action::Skip,
+                conditions: vec![Condition::Rain, Condition::Storm],
+            }
+        );
+        assert_eq!(Rule::parse(" "), Ok(None));
+        assert_eq!(Rule::parse("none"), Ok(None));
+    }
+
+    #[test]
+    fn rejects_what_it_cannot_do() {
+        let minutes = Err("Wake 1 to 180 minutes earlier, as in \"20m earlier\"".to_string());
+        assert_eq!(Rule::parse("0m earlier if rain"), minutes);
+        assert_eq!(Rule::parse("wake 181 minutes earlier if rain"), minutes);
+        assert_eq!(Rule::parse("soon earlier if rain"), minutes);
+        assert_eq!(
+            Rule::parse("skip when rain"),
+            Err("Weather rule needs \"if\", as in \"skip if rain\"".to_string())
+        );
+        assert_eq!(
+            Rule::parse("ring later if rain"),
+            Err("Unknown weather action \"ring later\"".to_string())
+        );
+        assert_eq!(
+            Rule::parse("skip if hail"),
+            Err("Unknown weather \"hail\"".to_string())
+        );
+        assert_eq!(
+            Rule::parse("skip if it's forecast"),
+            Err("Say which weather, as in \"skip if rain\"".to_string())
+        );
+    }
+
+    #[test]
+    fn displays_what_it_parses() {
+        for text in ["20m earlier if snow or freezing", "skip if rain or storm"] {
+            assert_eq!(rule(text).to_string(), text);
+        }
+    }
+
+    #[test]
+    fn looks_at_the_hours_before_the_alarm() {
+        let report = report(&[
+            (3, Sky::Snow, 1.0),
+            (4, Sky::Drizzle, 2.0),
+            (5, Sky::Clouds, -2.4),
+            (7, Sky::Clouds, 1.0),
+            (8, Sky::Thunderstorm, 3.0),
+        ]);
+        let due = at(7);
+        let evaluate = |input| rule(input).evaluate(&report, due, Units::Metric);
+
+        // 03:00 and 04:00 are past the lookback, 08:00 is after the alarm.
+        assert_eq!(evaluate("skip if snow"), None);
+        assert_eq!(evaluate("skip if rain or storm"), None);
+        assert_eq!(
+            evaluate("20m earlier if snow or freezing"),
+            Some(Adjustment {
+                action: Action::Earlier(20),
+                reason: "-2°C forecast".to_string(),
+            })
+        );
+        // 08:00 is within the hours before a later alarm.
+        assert_eq!(
+            rule("skip if rain")
+                .evaluate(&report, at(9), Units::Metric)
+                .map(|adjustment| adjustment.reason),
+            Some("rain forecast".to_string())
+        );
+        // Fahrenheit freezes at 32.
+        assert_eq!(
+            rule("skip if freezing").evaluate(&report, due, Units::Imperial),
+            Some(Adjustment {
+                action: Action::Skip,
+                reason: "-2°F forecast".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn goes_by_the_current_weather_without_a_forecast() {
+        let mut report = report(&[(20, Sky::Snow, -5.0)]);
+        let skip_if_snow = rule("skip if snow");
+        assert_eq!(skip_if_snow.evaluate(&report, at(7), Units::Metric), None);
+        report.current.sky = Sky::Snow;
+        assert_eq!(
+            skip_if_snow
+                .evaluate(&report, at(7), Units::Metric)
+                .map(|adjustment| adjustment.action),
+            Some(Action::Skip)
+        );
+        assert_eq!(
+            rule("skip if freezing").evaluate(&report, at(7), Units::Imperial),
+            Some(Adjustment {
+                action: Action::Skip,
+                reason: "8°F forecast".to_string(),
+            })
+        );
+    }
+}